@@ -28,7 +28,7 @@ struct CollisionTime {
 fn main() -> anyhow::Result<()> {
   let mut app = App::new();
   add_phase!(app, GamePhase, GamePhase::Bouncing,
-    start => [ setup ],
+    start => [ setup.after(ReseedRandom) ],
     run => [ warp_at_edge, collisions, show_performance,
       continual_parallax, physics_clock, sum_impulses, apply_velocity ],
     exit => [ cleanup::<BouncyElement> ]
@@ -51,7 +51,10 @@ fn main() -> anyhow::Result<()> {
       GamePhase::MainMenu,
       GamePhase::Bouncing,
       GamePhase::GameOver))
-    .add_plugins(RandomPlugin)
+    .add_plugins(RandomPlugin::new()
+      .with_seed(SeedPolicy::Arg("--seed"))
+      .with_seed(SeedPolicy::Env("BOUNCY_SEED"))
+      .reseed_on_enter(GamePhase::Bouncing))
     .add_plugins(
       AssetManager::new().add_image("green_ball", "green_ball.png")?,
     )
@@ -60,18 +63,31 @@ fn main() -> anyhow::Result<()> {
   Ok(())
 }
 
+const ARENA: Rect = Rect {
+  min: Vec2::new(-512.0, -384.0),
+  max: Vec2::new(512.0, 384.0),
+};
+
+// Balls are 8 pixels across: leave a gap so new balls don't start
+// out colliding.
+const SPAWN_SPACING: f32 = 10.0;
+
 fn spawn_bouncies(
   to_spawn: usize,
   commands: &mut Commands,
-  rng: &mut ResMut<RandomNumberGenerator>,
+  rng: &mut RandomNumberGenerator,
+  existing: &[Vec2],
   assets: &AssetStore,
   loaded_assets: &LoadedAssets,
 ) {
-  for _ in 0..to_spawn {
-    let position =
-      Vec3::new(rng.range(-512.0..512.0), rng.range(-384.0..384.0), 0.0);
+  // Fill the gaps between existing balls, stopping once there are
+  // enough. If the arena is full, fewer balls are added.
+  let positions = rng.poisson_disc_up_to(ARENA, SPAWN_SPACING, existing, to_spawn);
+  for position in positions {
+    let position = position.extend(0.0);
+    // Most balls drift gently, with the occasional fast one
     let velocity =
-      Vec3::new(rng.range(-1.0..1.0), rng.range(-1.0..1.0), 0.0);
+      Vec3::new(rng.normal(0.0, 0.4), rng.normal(0.0, 0.4), 0.0);
     spawn_image!(
       assets,
       commands,
@@ -89,7 +105,7 @@ fn spawn_bouncies(
 
 fn setup(
   mut commands: Commands,
-  mut rng: ResMut<RandomNumberGenerator>,
+  mut streams: ResMut<RandomStreams>,
   assets: Res<AssetStore>,
   loaded_assets: Res<LoadedAssets>,
 ) {
@@ -97,7 +113,8 @@ fn setup(
     .spawn(Camera2dBundle::default())
     .insert(BouncyElement);
   commands.insert_resource(CollisionTime::default());
-  spawn_bouncies(1, &mut commands, &mut rng, &assets, &loaded_assets);
+  spawn_bouncies(1, &mut commands, streams.stream("balls"), &[], &assets,
+    &loaded_assets);
 }
 
 fn warp_at_edge(mut query: Query<&mut Transform, With<Ball>>) {
@@ -122,12 +139,15 @@ fn show_performance(
   diagnostics: Res<DiagnosticsStore>,//(1)
   mut collision_time: ResMut<CollisionTime>,
   mut commands: Commands,
-  mut rng: ResMut<RandomNumberGenerator>,
+  mut streams: ResMut<RandomStreams>,
   assets: Res<AssetStore>,
   query: Query<&Transform, With<Ball>>,
   loaded_assets: Res<LoadedAssets>,
 ) {
   let n_balls = query.iter().count();//(2)
+  let existing = || -> Vec<Vec2> {
+    query.iter().map(|transform| transform.translation.truncate()).collect()
+  };
   let fps = diagnostics//(3)
     .get(FrameTimeDiagnosticsPlugin::FPS)
     .and_then(|fps| fps.average())
@@ -154,24 +174,24 @@ fn show_performance(
           "{n_balls}, {}, {}, {:.0}",
           collision_time.time, collision_time.checks, collision_time.fps
         );
-        spawn_bouncies(1, &mut commands, &mut rng, &assets, 
-          &loaded_assets);
+        spawn_bouncies(1, &mut commands, streams.stream("balls"),
+          &existing(), &assets, &loaded_assets);
       }
       if ui.button("Add 100 Balls").clicked() {
         println!(
           "{n_balls}, {}, {}, {:.0}",
           collision_time.time, collision_time.checks, collision_time.fps
         );
-        spawn_bouncies(100, &mut commands, &mut rng, &assets, 
-          &loaded_assets);
+        spawn_bouncies(100, &mut commands, streams.stream("balls"),
+          &existing(), &assets, &loaded_assets);
       }
       if ui.button("Add 1000 Balls").clicked() {
         println!(
           "{n_balls}, {}, {}, {:.0}",
           collision_time.time, collision_time.checks, collision_time.fps
         );
-        spawn_bouncies(1000, &mut commands, &mut rng, &assets, 
-          &loaded_assets);
+        spawn_bouncies(1000, &mut commands, streams.stream("balls"),
+          &existing(), &assets, &loaded_assets);
       }
     },
   );
@@ -16,6 +16,49 @@ struct Obstacle;
 #[derive(Component)]
 struct FlappyElement;
 
+// Added when flappy hits a wall, counting down to game over
+#[derive(Component)]
+struct Crashed(Timer);
+
+#[derive(Resource)]
+struct WallGaps {
+    nudges: ShuffleBag<i32>,
+    drift: Noise,
+    world: CoordinateRandom,
+    walls_built: u32,
+}
+
+impl WallGaps {
+    // Depends only on the wall's index, so the same wall always gets
+    // the same gap
+    fn gap(&self, wall: u32) -> i32 {
+        // The gap wanders smoothly up and down, with a small random nudge
+        let fractal = Fractal { octaves: 2, frequency: 0.3, ..default() };
+        let drift = self.drift.fbm_1d(wall as f32 + 0.5, &fractal) * 5.0;
+        (drift.round() as i32 + self.nudge(wall)).clamp(-5, 4)
+    }
+
+    // The drift changes slowly, so the nudges are what keep neighbouring
+    // gaps apart. They're dealt from a shuffle bag, so each block of
+    // walls gets the table's exact mix, without long runs of one nudge.
+    // Every block shuffles a fresh bag seeded by the block's index, so
+    // the nudge still depends only on the wall.
+    fn nudge(&self, wall: u32) -> i32 {
+        let size = self.nudges.len() as u32;
+        if size == 0 {
+            return 0;
+        }
+        let rng = &mut self.world.at((wall / size) as i64, 1).generator();
+        let mut bag = self.nudges.clone();
+        (0..=wall % size).filter_map(|_| bag.deal(rng).copied()).last().unwrap_or(0)
+    }
+
+    fn next(&mut self) -> i32 {
+        self.walls_built += 1;
+        self.gap(self.walls_built - 1)
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default, States)]
 enum GamePhase {
     #[default]
@@ -29,8 +72,9 @@ fn main() -> anyhow::Result<()> {
     let mut app = App::new();
 
     add_phase!(app, GamePhase, GamePhase::Flapping,
-      start => [ setup ],
-      run => [ flap, clamp, move_walls, hit_wall, cycle_animations,continual_parallax,
+      start => [ setup.after(ReseedRandom) ],
+      run => [ flap, animate_flappy, clamp, move_walls, hit_wall, crash,
+        animation_state_machines.before(cycle_animations), cycle_animations, camera_parallax,
         physics_clock, sum_impulses, apply_gravity, apply_velocity],
       exit => [ cleanup::<FlappyElement> ]
     );
@@ -43,12 +87,16 @@ fn main() -> anyhow::Result<()> {
         }),
         ..default()
     }))
-    .add_plugins(RandomPlugin)
+    .add_plugins(RandomPlugin::new()
+        .with_seed(SeedPolicy::Arg("--seed"))
+        .with_seed(SeedPolicy::Env("FLAPPY_SEED"))
+        .reseed_on_enter(GamePhase::Flapping))
     .add_plugins(GameStatePlugin::new(
         GamePhase::MainMenu,
         GamePhase::Flapping,
         GamePhase::GameOver,
     ))
+    .add_plugins(GameCameraPlugin)
     .add_plugins(
         AssetManager::new()
             .add_image("dragon", "flappy_dragon.png")?
@@ -58,40 +106,8 @@ fn main() -> anyhow::Result<()> {
             .add_image("bg_far", "rocky-nowater-far.png")?
             .add_image("bg_mid", "rocky-nowater-mid.png")?
             .add_image("bg_close", "rocky-nowater-close.png")?
-    )
-    .insert_resource(
-        Animations::new()
-            .with_animation(
-                "Straight and Level",
-                PerFrameAnimation::new(vec![
-                    AnimationFrame::new(2, 500, vec![AnimationOption::NextFrame]),
-                    AnimationFrame::new(3, 500, vec![AnimationOption::GoToFrame(0)]),
-                ]),
-            )
-            .with_animation(
-                "Flapping",
-                PerFrameAnimation::new(vec![
-                    AnimationFrame::new(
-                        0,
-                        66,
-                        vec![
-                            AnimationOption::NextFrame,
-                            AnimationOption::PlaySound("flap".to_string()),
-                        ],
-                    ),
-                    AnimationFrame::new(1, 66, vec![AnimationOption::NextFrame]),
-                    AnimationFrame::new(2, 66, vec![AnimationOption::NextFrame]),
-                    AnimationFrame::new(3, 66, vec![AnimationOption::NextFrame]),
-                    AnimationFrame::new(2, 66, vec![AnimationOption::NextFrame]),
-                    AnimationFrame::new(
-                        1,
-                        66,
-                        vec![AnimationOption::SwitchToAnimation(
-                            "Straight and Level".to_string(),
-                        )],
-                    ),
-                ]),
-            ),
+            .add_weighted_table("wall_gaps", "wall_gaps.table.ron")?
+            .add_animations("flappy_animations", "flappy.anim.ron")?
     )
     .run();
 
@@ -100,13 +116,35 @@ fn main() -> anyhow::Result<()> {
 
 fn setup(
     mut commands: Commands,
-    mut rng: ResMut<RandomNumberGenerator>,
+    mut streams: ResMut<RandomStreams>,
     assets: Res<AssetStore>,
     loaded_assets: AssetResource,
+    tables: Res<Assets<WeightedTableAsset>>,
 ) {
     commands
         .spawn(Camera2dBundle::default())
+        .insert(GameCamera::new(streams.stream("camera")))
         .insert(FlappyElement);
+    // Without the table the gaps still drift, just without the nudge
+    let table: WeightedTable<i32> = assets
+        .get_weighted_table("wall_gaps", &loaded_assets, &tables)
+        .unwrap_or_else(|e| {
+            error!("wall_gaps: {e:#}");
+            WeightedTable::new()
+        });
+    // One copy of each nudge per unit of weight
+    let nudges = table.entries.iter()
+        .flat_map(|entry| match &entry.value {
+            TableEntry::Item(nudge) => vec![*nudge; entry.weight as usize],
+            TableEntry::Table(_) => Vec::new(),
+        })
+        .collect();
+    let mut wall_gaps = WallGaps {
+        nudges,
+        drift: Noise::new(streams.stream("walls")),
+        world: CoordinateRandom::new(streams.stream("walls").next()),
+        walls_built: 0,
+    };
     spawn_animated_sprite!(
         assets,
         commands,
@@ -116,10 +154,12 @@ fn setup(
         10.0,
         "Straight and Level",
         Flappy { gravity: 0.0 },
+        flappy_animations(),
         FlappyElement,
-        Velocity::default(), ApplyGravity
+        Velocity::default(), ApplyGravity(0.2)
     );
-    build_wall(&mut commands, &assets, &loaded_assets, rng.range(-5..5));
+    build_wall(&mut commands, &assets, &loaded_assets, wall_gaps.next());
+    commands.insert_resource(wall_gaps);
     spawn_image!(
         assets,
         commands,
@@ -131,72 +171,18 @@ fn setup(
         FlappyElement,
         Velocity::new(-4.0, 0.0, 0.0)
     );
-    spawn_image!(
-        assets,
-        commands,
-        "bg_far",
-        0.0,
-        0.0,
-        2.0,
-        &loaded_assets,
-        FlappyElement,
-        ContinualParallax::new(1280.0, 66, Vec2::new(1.0, 0.0))
-    );
-    spawn_image!(
-        assets,
-        commands,
-        "bg_far",
-        1280.0,
-        0.0,
-        2.0,
-        &loaded_assets,
-        FlappyElement,
-        ContinualParallax::new(1280.0, 66, Vec2::new(1.0, 0.0))
-    );
-    spawn_image!(
-        assets,
-        commands,
-        "bg_mid",
-        0.0,
-        0.0,
-        3.0,
-        &loaded_assets,
-        FlappyElement,
-        ContinualParallax::new(1280.0, 33, Vec2::new(1.0, 0.0))
-    );
-    spawn_image!(
-        assets,
-        commands,
-        "bg_mid",
-        1280.0,
-        0.0,
-        3.0,
-        &loaded_assets,
-        FlappyElement,
-        ContinualParallax::new(1280.0, 33, Vec2::new(1.0, 0.0))
-    );
-    spawn_image!(
-        assets,
-        commands,
-        "bg_close",
-        0.0,
-        0.0,
-        4.0,
-        &loaded_assets,
-        FlappyElement,
-        ContinualParallax::new(1280.0, 16, Vec2::new(2.0, 0.0))
-    );
-    spawn_image!(
-        assets,
-        commands,
-        "bg_close",
-        1280.0,
-        0.0,
-        4.0,
-        &loaded_assets,
-        FlappyElement,
-        ContinualParallax::new(1280.0, 16, Vec2::new(2.0, 0.0))
-    );
+    // The camera only moves to shake, so the layers scroll by themselves.
+    // A factor of 1 pins them to the world, so they shake with the walls.
+    for (image, z, speed) in [("bg_far", 2.0, 15.0), ("bg_mid", 3.0, 30.0), ("bg_close", 4.0, 125.0)] {
+        commands
+            .spawn(SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, z)))
+            .insert(
+                Parallax::new(assets.get_handle(image, &loaded_assets).unwrap(), 1.0)
+                    .with_tiling(BVec2::new(true, false))
+                    .with_scroll(Vec2::new(-speed, 0.0)),
+            )
+            .insert(FlappyElement);
+    }
 }
 
 fn build_wall(
@@ -232,21 +218,45 @@ fn gravity(mut query: Query<(&mut Flappy, &mut Transform)>) {
 }
  */
 fn flap(keyboard: Res<Input<KeyCode>>, 
-        mut query: Query<(Entity, &mut AnimationCycle)>,
+        query: Query<Entity, With<Flappy>>,
         mut impulse: EventWriter<Impulse>
 ) {
     if keyboard.pressed(KeyCode::Space) {
-        if let Ok((mut flappy, mut animation)) = query.get_single_mut() {
+        if let Ok(flappy) = query.get_single() {
             //flappy.gravity = -5.0;
             impulse.send(Impulse{
                 target: flappy, amount: Vec3::Y,
                 absolute: false
             });
-            animation.switch("Flapping");
         }
     }
 }
 
+fn flappy_animations() -> AnimationStateMachine {
+    AnimationStateMachine::new("gliding")
+        .with_state("gliding", "Straight and Level")
+        .with_state("flapping", "Flapping")
+        .with_float("vertical_speed", 0.0)
+        .with_transition(AnimationTransition::new("gliding", "flapping")
+            .when(AnimationCondition::above("vertical_speed", 0.0)))
+        // "Flapping" loops (its last frame goes back to the first), so the
+        // wings keep beating while flappy climbs. Once falling, glide, but
+        // only after at least one whole beat, so a tap never cuts it short.
+        .with_transition(AnimationTransition::new("flapping", "gliding")
+            .when(AnimationCondition::below("vertical_speed", 0.0))
+            .after(1.0))
+}
+
+fn animate_flappy(
+    mut query: Query<(&Velocity, &mut AnimationStateMachine, &mut AnimationCycle), With<Flappy>>,
+) {
+    if let Ok((velocity, mut machine, mut animation)) = query.get_single_mut() {
+        machine.set_float("vertical_speed", velocity.0.y);
+        // Beat faster while climbing, and glide while falling
+        animation.set_speed((1.0 + velocity.0.y * 0.25).clamp(0.5, 2.5));
+    }
+}
+
 fn clamp(mut query: Query<&mut Transform, With<Flappy>>, mut state: ResMut<NextState<GamePhase>>) {
     if let Ok(mut transform) = query.get_single_mut() {
         if transform.translation.y > 384.0 {
@@ -263,7 +273,7 @@ fn move_walls(
     delete: Query<Entity, With<Obstacle>>,
     assets: Res<AssetStore>,
     loaded_assets: Res<LoadedAssets>, //&LoadedAssets,
-    mut rng: ResMut<RandomNumberGenerator>,
+    mut wall_gaps: ResMut<WallGaps>,
 ) {
     let mut rebuild = false;
     for transform in query.iter() {
@@ -277,21 +287,40 @@ fn move_walls(
         for entity in delete.iter() {
             commands.entity(entity).despawn();
         }
-        build_wall(&mut commands, &assets, &loaded_assets, rng.range(-5..5));
+        build_wall(&mut commands, &assets, &loaded_assets, wall_gaps.next());
     }
 }
 
 fn hit_wall(
-    player: Query<&Transform, With<Flappy>>,
+    mut commands: Commands,
+    player: Query<(Entity, &Transform), With<Flappy>>,
+    crashed: Query<(), With<Crashed>>,
     walls: Query<&Transform, With<Obstacle>>,
+    mut cameras: Query<&mut GameCamera>,
+) {
+    if !crashed.is_empty() {
+        return;
+    }
+    if let Ok((flappy, player)) = player.get_single() {
+        let hit = walls
+            .iter()
+            .any(|wall| player.translation.distance(wall.translation) < 32.0);
+        if hit {
+            commands.entity(flappy).insert(Crashed(Timer::from_seconds(0.5, TimerMode::Once)));
+            cameras.for_each_mut(|mut camera| camera.add_trauma(0.8));
+        }
+    }
+}
+
+// Lets the screen shake before the game over menu appears
+fn crash(
+    mut crashed: Query<&mut Crashed>,
+    time: Res<Time>,
     mut state: ResMut<NextState<GamePhase>>,
 ) {
-    if let Ok(player) = player.get_single() {
-        for wall in walls.iter() {
-            let distance = player.translation.distance(wall.translation);
-            if distance < 32.0 {
-                state.set(GamePhase::GameOver);
-            }
+    for mut crashed in crashed.iter_mut() {
+        if crashed.0.tick(time.delta()).just_finished() {
+            state.set(GamePhase::GameOver);
         }
     }
 }
@@ -16,10 +16,54 @@ struct GameElement;
 #[derive(Component)]
 struct Player;
 
+#[derive(Resource)]
+struct Cavern {
+  map: mapgen::Grid<mapgen::Tile>,
+}
+
+impl Cavern {
+  // Size of each rock tile, in pixels
+  const TILE: f32 = 16.0;
+
+  fn generate(rng: &mut RandomNumberGenerator) -> Self {
+    use mapgen::*;
+    // Two screens across and two down; the camera follows the ship
+    let (width, height) = ((2048.0 / Self::TILE) as i32, (1536.0 / Self::TILE) as i32);
+    let caves = CellularAutomata { width, height, wall_chance: 0.42, ..default() }
+      .generate(rng);
+    let ground = HeightMap { width, height, ground: 0.15, amplitude: 0.12, ..default() }
+      .generate(rng);
+    let start = IVec2::new(width / 2, height / 2);
+    let mut map = caves.map(|pos, tile| {
+      if ground[pos] == Tile::Wall {
+        Tile::Wall
+      } else if pos.as_vec2().distance(start.as_vec2()) < 5.0 {
+        // Clear some space around the ship
+        Tile::Floor
+      } else {
+        *tile
+      }
+    });
+    map.keep_connected(start);
+    Self { map }
+  }
+
+  // The map's extent in the world
+  fn bounds(&self) -> Rect {
+    let size = Vec2::new(self.map.width() as f32, self.map.height() as f32) * Self::TILE;
+    Rect::from_center_size(Vec2::ZERO, size)
+  }
+
+  fn is_solid(&self, position: Vec2) -> bool {
+    let cell = self.map.from_world(position, Self::TILE);
+    self.map.get(cell) != Some(&mapgen::Tile::Floor)
+  }
+}
+
 fn main() -> anyhow::Result<()> {
   let mut app = App::new();
   add_phase!(app, GamePhase, GamePhase::Playing,
-    start => [ setup ],
+    start => [ setup.after(ReseedRandom) ],
     run => [ movement, end_game, physics_clock, sum_impulses, apply_gravity, 
       apply_velocity, terminal_velocity ],
     exit => [ cleanup::<GameElement> ]
@@ -36,13 +80,22 @@ fn main() -> anyhow::Result<()> {
       }),
       ..default()
     }))
-    .add_plugins(RandomPlugin)
+    .add_plugins(RandomPlugin::new()
+      .with_seed(SeedPolicy::Arg("--seed"))
+      .with_seed(SeedPolicy::Env("MARS_SEED"))
+      .reseed_on_enter(GamePhase::Playing))
     .add_plugins(GameStatePlugin::new(
       GamePhase::MainMenu,
       GamePhase::Playing,
       GamePhase::GameOver,
     ))
-    .add_plugins(AssetManager::new().add_image("ship", "ship.png")?)
+    .add_plugins(GameCameraPlugin)
+    .add_plugins(
+      AssetManager::new()
+        .add_image("ship", "ship.png")?
+        .add_image("rock", "rock.png")?
+        .add_word_list("base_names", "mars_bases.words.txt")?,
+    )
     .insert_resource(Animations::new())
     .run();
 
@@ -53,11 +106,37 @@ fn setup(
   mut commands: Commands,
   assets: Res<AssetStore>,
   loaded_assets: Res<LoadedAssets>,
+  mut streams: ResMut<RandomStreams>,
+  word_lists: Res<Assets<WordListAsset>>,
+  mut windows: Query<&mut Window>,
 ) {
-  commands
-    .spawn(Camera2dBundle::default())
-    .insert(GameElement);
-  spawn_image!(
+  let base_names = assets
+    .get_word_list("base_names", &loaded_assets, &word_lists)
+    .unwrap()
+    .names(2)
+    .with_length(4, 10);
+  if let Some(name) = base_names.generate(streams.stream("base_name")) {
+    windows.single_mut().title = format!("Mars Base {name}");
+  }
+  let cavern = Cavern::generate(streams.stream("cavern"));
+  for (pos, tile) in cavern.map.iter() {
+    if *tile == mapgen::Tile::Wall {
+      let position = cavern.map.to_world(pos, Cavern::TILE);
+      spawn_image!(
+        assets,
+        commands,
+        "rock",
+        position.x,
+        position.y,
+        0.5,
+        &loaded_assets,
+        GameElement
+      );
+    }
+  }
+  let bounds = cavern.bounds();
+  commands.insert_resource(cavern);
+  let player = spawn_image!(
     assets,
     commands,
     "ship",
@@ -70,17 +149,23 @@ fn setup(
     Velocity::default(),
     PhysicsPosition::new(Vec2::new(0.0, 0.0)),
     ApplyGravity(0.2)
-  );
+  ).id();
+  commands
+    .spawn(Camera2dBundle::default())
+    .insert(GameCamera::new(streams.stream("camera"))
+      .following(player)
+      .with_dead_zone(Vec2::new(256.0, 192.0))
+      .with_bounds(bounds))
+    .insert(GameElement);
 }
 
 fn end_game(
   mut state: ResMut<NextState<GamePhase>>,
   player_query: Query<&Transform, With<Player>>,
+  cavern: Res<Cavern>,
 ) {
   let transform = player_query.single();
-  if transform.translation.y < -384.0 || transform.translation.y > 384.0 ||
-      transform.translation.x < -512.0 || transform.translation.x > 512.0
-  {
+  if cavern.is_solid(transform.translation.truncate()) {
     state.set(GamePhase::GameOver);
   }
 }
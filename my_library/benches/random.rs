@@ -1,4 +1,4 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use my_library::*;
 
 pub fn criterion_benchmark(c: &mut Criterion) {
@@ -10,5 +10,47 @@ pub fn criterion_benchmark(c: &mut Criterion) {
   });
 }
 
-criterion_group!(benches, criterion_benchmark);
+pub fn algorithm_benchmark(c: &mut Criterion) {
+  let mut group = c.benchmark_group("algorithms");
+  for algorithm in Algorithm::ALL {
+    let rng = &mut RandomNumberGenerator::with_algorithm(algorithm, 1);
+    group.bench_with_input(
+      BenchmarkId::new("next_u64", format!("{algorithm:?}")),
+      &algorithm,
+      |b, _| b.iter(|| rng.next::<u64>()),
+    );
+    group.bench_with_input(
+      BenchmarkId::new("range_f32", format!("{algorithm:?}")),
+      &algorithm,
+      |b, _| b.iter(|| rng.range(1.0_f32..10_000_000_f32)),
+    );
+    group.bench_with_input(
+      BenchmarkId::new("roll_3d6", format!("{algorithm:?}")),
+      &algorithm,
+      |b, _| b.iter(|| rng.range(1..=6) + rng.range(1..=6) + rng.range(1..=6)),
+    );
+  }
+  group.finish();
+}
+
+pub fn distribution_benchmark(c: &mut Criterion) {
+  let mut group = c.benchmark_group("distributions");
+  let rng = &mut RandomNumberGenerator::new();
+  group.bench_function("normal", |b| b.iter(|| rng.normal(0.0, 1.0)));
+  group.bench_function("exponential", |b| b.iter(|| rng.exponential(2.0)));
+  group.bench_function("poisson", |b| b.iter(|| rng.poisson(3.0)));
+  group.bench_function("binomial", |b| b.iter(|| rng.binomial(20, 0.3)));
+  group.bench_function("triangular", |b| {
+    b.iter(|| rng.triangular(0.0, 1.0, 0.25))
+  });
+  group.bench_function("beta", |b| b.iter(|| rng.beta(2.0, 5.0)));
+  group.finish();
+}
+
+criterion_group!(
+  benches,
+  criterion_benchmark,
+  algorithm_benchmark,
+  distribution_benchmark
+);
 criterion_main!(benches);
@@ -8,8 +8,7 @@ fn main() {
   let mut results = vec![0; 16];
   // Roll 1,000 sets of 3d6 and increment results to map distribution
   for _ in 0..1_000 {
-    let roll: usize =
-      rng.range(1..=6) + rng.range(1..=6) + rng.range(1..=6);
+    let roll = rng.roll_dice("3d6").unwrap().total as usize;
     results[roll - 3] += 1;
   }
   // Print the distribution histogram
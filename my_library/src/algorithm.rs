@@ -0,0 +1,107 @@
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+/// The random number generation algorithms [`RandomNumberGenerator`](crate::RandomNumberGenerator)
+/// can use. Every algorithm is always available through
+/// `RandomNumberGenerator::with_algorithm`; the `pcg` and `xorshift`
+/// feature flags only choose the [default](Algorithm::default).
+///
+/// Features add up across a dependency graph, so both flags may end up
+/// enabled at once. When they are, `pcg` wins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Algorithm {
+  /// [PCG](https://crates.io/crates/rand_pcg) (`Pcg64Mcg`): fast, small
+  /// and statistically strong. The default.
+  Pcg64,
+  /// [XorShift](https://crates.io/crates/rand_xorshift): very fast, but
+  /// fails some statistical tests.
+  XorShift,
+  /// ChaCha with 12 rounds, the algorithm behind `rand`'s `StdRng`.
+  /// Slower, but cryptographically strong.
+  ChaCha12,
+}
+
+impl Algorithm {
+  /// Every available algorithm, for comparing them.
+  pub const ALL: [Algorithm; 3] = [Algorithm::Pcg64, Algorithm::XorShift, Algorithm::ChaCha12];
+}
+
+impl Default for Algorithm {
+  #[cfg(feature = "pcg")]
+  fn default() -> Self {
+    Algorithm::Pcg64
+  }
+
+  #[cfg(all(feature = "xorshift", not(feature = "pcg")))]
+  fn default() -> Self {
+    Algorithm::XorShift
+  }
+
+  #[cfg(all(not(feature = "pcg"), not(feature = "xorshift")))]
+  fn default() -> Self {
+    Algorithm::ChaCha12
+  }
+}
+
+/// The generator behind a `RandomNumberGenerator`, chosen at runtime.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Backend {
+  Pcg64(rand_pcg::Pcg64Mcg),
+  XorShift(rand_xorshift::XorShiftRng),
+  // Boxed: its block buffer is far larger than the other generators
+  ChaCha12(Box<rand_chacha::ChaCha12Rng>),
+}
+
+macro_rules! dispatch {
+  ($backend:expr, $rng:ident => $body:expr) => {
+    match $backend {
+      Backend::Pcg64($rng) => $body,
+      Backend::XorShift($rng) => $body,
+      Backend::ChaCha12($rng) => $body,
+    }
+  };
+}
+
+impl Backend {
+  pub(crate) fn from_entropy(algorithm: Algorithm) -> Self {
+    match algorithm {
+      Algorithm::Pcg64 => Backend::Pcg64(SeedableRng::from_entropy()),
+      Algorithm::XorShift => Backend::XorShift(SeedableRng::from_entropy()),
+      Algorithm::ChaCha12 => Backend::ChaCha12(Box::new(SeedableRng::from_entropy())),
+    }
+  }
+
+  pub(crate) fn seed_from_u64(algorithm: Algorithm, seed: u64) -> Self {
+    match algorithm {
+      Algorithm::Pcg64 => Backend::Pcg64(SeedableRng::seed_from_u64(seed)),
+      Algorithm::XorShift => Backend::XorShift(SeedableRng::seed_from_u64(seed)),
+      Algorithm::ChaCha12 => Backend::ChaCha12(Box::new(SeedableRng::seed_from_u64(seed))),
+    }
+  }
+
+  pub(crate) fn algorithm(&self) -> Algorithm {
+    match self {
+      Backend::Pcg64(_) => Algorithm::Pcg64,
+      Backend::XorShift(_) => Algorithm::XorShift,
+      Backend::ChaCha12(_) => Algorithm::ChaCha12,
+    }
+  }
+}
+
+impl rand::RngCore for Backend {
+  fn next_u32(&mut self) -> u32 {
+    dispatch!(self, rng => rng.next_u32())
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    dispatch!(self, rng => rng.next_u64())
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    dispatch!(self, rng => rng.fill_bytes(dest))
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+    dispatch!(self, rng => rng.try_fill_bytes(dest))
+  }
+}
@@ -0,0 +1,199 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    reflect::TypePath,
+    utils::{BoxedFuture, HashMap},
+};
+use std::collections::BTreeMap;
+use crate::{Animations, PerFrameAnimation};
+
+/// The contents of a `.anim.ron` file, loaded by [`AssetManager`](crate::AssetManager):
+/// a map from animation tags to their frames.
+///
+/// ```text
+/// {
+///     "Straight and Level": [
+///         (sprite_index: 2, delay_ms: 500, action: [NextFrame]),
+///         (sprite_index: 3, delay_ms: 500, action: [GoToFrame(0)]),
+///     ],
+/// }
+/// ```
+///
+/// Loaded animations are merged into the [`Animations`] resource, and
+/// merged again whenever the file changes (with Bevy's `file_watcher`
+/// feature and asset watching enabled), so timings can be tuned while
+/// the game runs.
+#[derive(Asset, TypePath, Debug)]
+pub struct AnimationsAsset {
+    animations: Animations,
+}
+
+impl AnimationsAsset {
+    /// The animations in the file.
+    pub fn animations(&self) -> &Animations {
+        &self.animations
+    }
+}
+
+/// Assets that bring their own animations, merged into [`Animations`]
+/// by [`sync_animations`].
+pub(crate) trait AnimationSource: Asset {
+    fn animations(&self) -> &Animations;
+}
+
+impl AnimationSource for AnimationsAsset {
+    fn animations(&self) -> &Animations {
+        &self.animations
+    }
+}
+
+pub(crate) fn parse_animations(source: &str) -> anyhow::Result<Animations> {
+    let frames: BTreeMap<String, PerFrameAnimation> = ron::from_str(source)?;
+    let animations = Animations(frames.into_iter().collect());
+    // Each file must be self-contained, so a typo in a frame reference
+    // is caught while loading rather than mid-game
+    animations.validate()?;
+    Ok(animations)
+}
+
+#[derive(Default)]
+pub(crate) struct AnimationsLoader;
+
+impl AssetLoader for AnimationsLoader {
+    type Asset = AnimationsAsset;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut source = String::new();
+            reader.read_to_string(&mut source).await?;
+            let animations = parse_animations(&source).map_err(|e| {
+                anyhow::Error::msg(format!("{}: {e}", load_context.path().display()))
+            })?;
+            Ok(AnimationsAsset { animations })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["anim.ron"]
+    }
+}
+
+/// Copies newly loaded (or hot reloaded) animation files into the
+/// [`Animations`] resource, creating it if needed. Animations a file
+/// no longer contains (or a removed file contained) are dropped.
+pub(crate) fn sync_animations<A: AnimationSource>(
+    mut events: EventReader<AssetEvent<A>>,
+    files: Res<Assets<A>>,
+    animations: Option<ResMut<Animations>>,
+    // The animation names each file added last time
+    mut added: Local<HashMap<AssetId<A>, Vec<String>>>,
+    mut commands: Commands,
+) {
+    let mut merged = animations.as_deref().cloned().unwrap_or_default();
+    let mut changed = false;
+    for event in events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id } | AssetEvent::Removed { id }) = event else {
+            continue;
+        };
+        if let Some(names) = added.remove(id) {
+            for name in names {
+                merged.0.remove(&name);
+            }
+            changed = true;
+        }
+        if matches!(event, AssetEvent::Removed { .. }) {
+            continue;
+        }
+        if let Some(file) = files.get(*id) {
+            if matches!(event, AssetEvent::Modified { .. }) {
+                info!("Reloaded {} animations", file.animations().0.len());
+            }
+            merged.0.extend(file.animations().0.iter().map(|(k, v)| (k.clone(), v.clone())));
+            added.insert(*id, file.animations().0.keys().cloned().collect());
+            changed = true;
+        }
+    }
+    if changed {
+        match animations {
+            Some(mut animations) => *animations = merged,
+            None => commands.insert_resource(merged),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::AnimationOption;
+
+    #[test]
+    fn test_parse_animations() {
+        let animations = parse_animations(r#"{
+            "Idle": [
+                (sprite_index: 2, delay_ms: 500, action: [NextFrame]),
+                (sprite_index: 3, delay_ms: 500, action: [GoToFrame(0)]),
+            ],
+            "Flap": [
+                (sprite_index: 0, delay_ms: 66, action: [NextFrame, PlaySound("flap")]),
+                (sprite_index: 1, delay_ms: 66, action: [Emit("wings_down"), SwitchToAnimation("Idle")]),
+            ],
+        }"#).unwrap();
+        assert_eq!(animations.0.len(), 2);
+        assert_eq!(animations.0["Flap"].frames.len(), 2);
+        assert_eq!(
+            animations.0["Flap"].frames[1].action[0],
+            AnimationOption::Emit("wings_down".to_string())
+        );
+    }
+
+    #[test]
+    fn test_bad_frame_references() {
+        let error = |source: &str| parse_animations(source).unwrap_err().to_string();
+        assert!(error(r#"{ "A": [(sprite_index: 0, delay_ms: 1, action: [GoToFrame(3)])] }"#)
+            .contains("GoToFrame(3)"));
+        assert!(error(r#"{ "A": [(sprite_index: 0, delay_ms: 1, action: [NextFrame])] }"#)
+            .contains("NextFrame"));
+        assert!(error(r#"{ "A": [(sprite_index: 0, delay_ms: 1, action: [SwitchToAnimation("B")])] }"#)
+            .contains("\"B\""));
+        assert!(error(r#"{ "A": [] }"#).contains("no frames"));
+        assert!(error(r#"{ "A": [(sprite_index: 0)] }"#).contains("delay_ms"));
+    }
+    #[test]
+    fn test_reload_drops_removed_animations() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<Assets<AnimationsAsset>>()
+            .add_event::<AssetEvent<AnimationsAsset>>()
+            .add_systems(Update, sync_animations::<AnimationsAsset>);
+        let file = |source: &str| AnimationsAsset { animations: parse_animations(source).unwrap() };
+        let frames = "[(sprite_index: 0, delay_ms: 1, action: [])]";
+        let handle = app.world.resource_mut::<Assets<AnimationsAsset>>()
+            .add(file(&format!(r#"{{ "Idle": {frames}, "Walk": {frames} }}"#)));
+        app.world.send_event(AssetEvent::Added { id: handle.id() });
+        app.update();
+        let names = |app: &App| {
+            let mut names: Vec<String> = app.world.resource::<Animations>().0.keys().cloned().collect();
+            names.sort();
+            names
+        };
+        assert_eq!(names(&app), vec!["Idle", "Walk"]);
+
+        // "Walk" is renamed to "Run"
+        app.world.resource_mut::<Assets<AnimationsAsset>>()
+            .insert(handle.id(), file(&format!(r#"{{ "Idle": {frames}, "Run": {frames} }}"#)));
+        app.world.send_event(AssetEvent::Modified { id: handle.id() });
+        app.update();
+        assert_eq!(names(&app), vec!["Idle", "Run"]);
+
+        app.world.send_event(AssetEvent::Removed { id: handle.id() });
+        app.update();
+        assert!(names(&app).is_empty());
+    }
+}
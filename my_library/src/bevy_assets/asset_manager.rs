@@ -1,10 +1,18 @@
 use bevy::prelude::*;
 use crate::bevy_assets::asset_store::*;
+use crate::bevy_assets::weighted_table_asset::*;
+use crate::bevy_assets::word_list_asset::*;
+use crate::bevy_assets::animations_asset::*;
+use crate::bevy_assets::sprite_sheet_asset::*;
 #[derive(Clone)]
 pub enum AssetType {
     Image,
     Sound,
     SpriteSheet{tile_size: Vec2, sprites_x: usize, sprites_y: usize},
+    SpriteSheetJson,
+    WeightedTable,
+    WordList,
+    Animations,
 }
 
 #[derive(Resource, Clone)]
@@ -84,10 +92,115 @@ impl AssetManager {
             }));
         Ok(self)
     }
+    /// Adds a sprite sheet exported from Aseprite or TexturePacker as
+    /// JSON (see [`SpriteSheetAsset`]), for sheets that aren't a uniform
+    /// grid. The filename must end in `.sheet.json`; the image it names
+    /// is loaded too. Once loaded, the sheet's atlas is available with
+    /// [`AssetStore::get_atlas_handle`] and its animations are merged
+    /// into [`Animations`](crate::Animations), keyed by tag name.
+    pub fn add_sprite_sheet_json<S: ToString>(
+        mut self,
+        tag: S,
+        filename: S,
+    ) -> anyhow::Result<Self> {
+        let filename = filename.to_string();
+        if !filename.ends_with(".sheet.json") {
+            return Err(anyhow::Error::msg(format!(
+                "{} is not a .sheet.json file",
+                &filename
+            )));
+        }
+        AssetManager::asset_exists(&filename)?;
+        self
+            .asset_list
+            .push((tag.to_string(), filename, AssetType::SpriteSheetJson));
+        Ok(self)
+    }
+    /// Adds a [`WeightedTable`](crate::WeightedTable) stored as RON. The
+    /// filename must end in `.table.ron`. Once loaded, fetch it with
+    /// [`AssetStore::get_weighted_table`].
+    pub fn add_weighted_table<S: ToString>(
+        mut self,
+        tag: S,
+        filename: S,
+    ) -> anyhow::Result<Self> {
+        let filename = filename.to_string();
+        if !filename.ends_with(".table.ron") {
+            return Err(anyhow::Error::msg(format!(
+                "{} is not a .table.ron file",
+                &filename
+            )));
+        }
+        AssetManager::asset_exists(&filename)?;
+        self
+            .asset_list
+            .push((tag.to_string(), filename, AssetType::WeightedTable));
+        Ok(self)
+    }
+    /// Adds a word list: a text file with one name (or sentence) per
+    /// line, used to train a [`Markov`](crate::Markov) generator. The
+    /// filename must end in `.words.txt`. Once loaded, fetch it with
+    /// [`AssetStore::get_word_list`].
+    pub fn add_word_list<S: ToString>(
+        mut self,
+        tag: S,
+        filename: S,
+    ) -> anyhow::Result<Self> {
+        let filename = filename.to_string();
+        if !filename.ends_with(".words.txt") {
+            return Err(anyhow::Error::msg(format!(
+                "{} is not a .words.txt file",
+                &filename
+            )));
+        }
+        AssetManager::asset_exists(&filename)?;
+        self
+            .asset_list
+            .push((tag.to_string(), filename, AssetType::WordList));
+        Ok(self)
+    }
+    /// Adds a set of animations stored as RON (see [`AnimationsAsset`]).
+    /// The filename must end in `.anim.ron`. Once loaded, its
+    /// animations are merged into the [`Animations`](crate::Animations)
+    /// resource, and updated live when the file is hot reloaded.
+    ///
+    /// Each file is validated on its own when it loads, so a
+    /// `SwitchToAnimation` must name an animation in the same file.
+    /// Keep animations that switch between each other together.
+    pub fn add_animations<S: ToString>(
+        mut self,
+        tag: S,
+        filename: S,
+    ) -> anyhow::Result<Self> {
+        let filename = filename.to_string();
+        if !filename.ends_with(".anim.ron") {
+            return Err(anyhow::Error::msg(format!(
+                "{} is not a .anim.ron file",
+                &filename
+            )));
+        }
+        AssetManager::asset_exists(&filename)?;
+        self
+            .asset_list
+            .push((tag.to_string(), filename, AssetType::Animations));
+        Ok(self)
+    }
 }
 
 impl Plugin for AssetManager {
     fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_asset::<WeightedTableAsset>();
+        app.init_asset_loader::<WeightedTableLoader>();
+        app.init_asset::<WordListAsset>();
+        app.init_asset_loader::<WordListLoader>();
+        app.init_asset::<AnimationsAsset>();
+        app.init_asset_loader::<AnimationsLoader>();
+        app.init_asset::<SpriteSheetAsset>();
+        app.init_asset_loader::<SpriteSheetLoader>();
+        app.add_systems(Update, (
+            sync_animations::<AnimationsAsset>,
+            sync_animations::<SpriteSheetAsset>,
+        ));
         app.insert_resource(self.clone());
         app.add_systems(Startup, setup);
     }
@@ -101,6 +214,7 @@ fn setup(
     let mut assets = AssetStore {
         asset_index: bevy::utils::HashMap::new(),
         atlases_to_build: Vec::new(),
+        sheets_to_build: Vec::new(),
         atlases: bevy::utils::HashMap::new(),
     };
     asset_resource.asset_list.iter().for_each(
@@ -124,6 +238,13 @@ fn setup(
                         sprites_y: *sprites_y,
                     });
                 }
+                AssetType::SpriteSheetJson => {
+                    // The atlas is built from the sheet by the loading menu
+                    assets
+                        .asset_index
+                        .insert(tag.clone(), asset_server.load_untyped(filename));
+                    assets.sheets_to_build.push(tag.clone());
+                }
                 _ => {
                     // Most asset types don't require a separate loader
                     assets
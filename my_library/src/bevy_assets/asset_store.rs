@@ -3,6 +3,8 @@ use bevy::{
     prelude::*,
     utils::HashMap,
 };
+use serde::de::DeserializeOwned;
+use crate::{SpriteSheetAsset, WeightedTable, WeightedTableAsset, WordListAsset};
 
 pub type LoadedAssets = Assets<LoadedUntypedAsset>;
 pub type AssetResource<'w> = Res<'w, LoadedAssets>;
@@ -18,6 +20,7 @@ pub(crate) struct FutureAtlas {
 pub struct AssetStore {
     pub(crate) asset_index: HashMap<String, Handle<LoadedUntypedAsset>>,
     pub(crate) atlases_to_build: Vec<FutureAtlas>,
+    pub(crate) sheets_to_build: Vec<String>,
     pub(crate) atlases: HashMap<String, Handle<TextureAtlas>>,
 }
 
@@ -45,6 +48,59 @@ impl AssetStore {
         }
         None
     }
+    /// Builds the [`WeightedTable`] registered as `index` with
+    /// [`AssetManager::add_weighted_table`](crate::AssetManager::add_weighted_table).
+    /// Each call returns a fresh table, with its pity timers reset.
+    pub fn get_weighted_table<T>(
+        &self,
+        index: &str,
+        assets: &LoadedAssets,
+        tables: &Assets<WeightedTableAsset>,
+    ) -> anyhow::Result<WeightedTable<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let handle = self
+            .get_handle::<WeightedTableAsset>(index, assets)
+            .ok_or_else(|| anyhow::Error::msg(format!("{index} is not loaded")))?;
+        let table = tables
+            .get(&handle)
+            .ok_or_else(|| anyhow::Error::msg(format!("{index} is not a weighted table")))?;
+        table.table()
+    }
+    /// Fetches the word list registered as `index` with
+    /// [`AssetManager::add_word_list`](crate::AssetManager::add_word_list).
+    /// Train a generator from it with [`WordListAsset::names`] or
+    /// [`WordListAsset::text`].
+    pub fn get_word_list<'a>(
+        &self,
+        index: &str,
+        assets: &LoadedAssets,
+        lists: &'a Assets<WordListAsset>,
+    ) -> anyhow::Result<&'a WordListAsset> {
+        let handle = self
+            .get_handle::<WordListAsset>(index, assets)
+            .ok_or_else(|| anyhow::Error::msg(format!("{index} is not loaded")))?;
+        lists
+            .get(&handle)
+            .ok_or_else(|| anyhow::Error::msg(format!("{index} is not a word list")))
+    }
+    /// Fetches the sprite sheet registered as `index` with
+    /// [`AssetManager::add_sprite_sheet_json`](crate::AssetManager::add_sprite_sheet_json),
+    /// for its frames and slices.
+    pub fn get_sprite_sheet<'a>(
+        &self,
+        index: &str,
+        assets: &LoadedAssets,
+        sheets: &'a Assets<SpriteSheetAsset>,
+    ) -> anyhow::Result<&'a SpriteSheetAsset> {
+        let handle = self
+            .get_handle::<SpriteSheetAsset>(index, assets)
+            .ok_or_else(|| anyhow::Error::msg(format!("{index} is not loaded")))?;
+        sheets
+            .get(&handle)
+            .ok_or_else(|| anyhow::Error::msg(format!("{index} is not a sprite sheet")))
+    }
     pub fn play(&self,
                 sound_name: &str,
                 commands: &mut Commands,
@@ -1,13 +1,17 @@
-use crate::{egui::egui::Window, AssetStore, MenuResource};
+use crate::{egui::egui::Window, AssetStore, MenuResource, SpriteSheetAsset};
 use bevy::{asset::LoadedUntypedAsset, prelude::*};
 use bevy_egui::EguiContexts;
 
 #[derive(Resource)]
-pub(crate) struct AssetsToLoad(Vec<Handle<LoadedUntypedAsset>>);
+pub(crate) struct AssetsToLoad {
+    loading: Vec<Handle<LoadedUntypedAsset>>,
+    // Paths of assets that failed to load, shown instead of the count
+    failed: Vec<String>,
+}
 pub(crate) fn setup(assets: Res<AssetStore>, mut commands: Commands) {
     let assets_to_load: Vec<Handle<LoadedUntypedAsset>> =
         assets.asset_index.values().cloned().collect();
-    commands.insert_resource(AssetsToLoad(assets_to_load));
+    commands.insert_resource(AssetsToLoad { loading: assets_to_load, failed: Vec::new() });
 }
 pub(crate) fn run<T>(
     asset_server: Res<AssetServer>,
@@ -18,29 +22,46 @@ pub(crate) fn run<T>(
     mut store: ResMut<AssetStore>,
     loaded_assets: Res<crate::LoadedAssets>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    sheets: Res<Assets<SpriteSheetAsset>>,
 ) where T: States,
 {
-    to_load.0.retain(|handle| {
+    let AssetsToLoad { loading, failed } = &mut *to_load;
+    loading.retain(|handle| {
         match asset_server.get_load_state(handle.id()) {
             Some(bevy::asset::LoadState::Loaded) => false,
+            // The loader has already logged why; stay on this screen
+            // rather than start a game that's missing assets
+            Some(bevy::asset::LoadState::Failed) => {
+                let path = handle.path().map_or("an asset".to_string(), |path| path.path().display().to_string());
+                error!("{path} failed to load");
+                failed.push(path);
+                false
+            }
             _ => true,
         }
     });
-    if to_load.0.is_empty() {
-        load_atlases(&mut store, &loaded_assets, &mut texture_atlases);
+    if loading.is_empty() && failed.is_empty() {
+        load_atlases(&mut store, &loaded_assets, &mut texture_atlases, &sheets);
         state.set(menu_info.menu_state.clone());
     }
     Window::new("Loading, Please Wait").show(
         egui_context.ctx_mut(), |ui| {
-            ui.label(
-                format!("{} assets remaining", to_load.0.len())
-            )
+            if failed.is_empty() {
+                ui.label(
+                    format!("{} assets remaining", loading.len())
+                );
+            } else {
+                for path in failed.iter() {
+                    ui.label(format!("Failed to load {path}"));
+                }
+            }
         });
 }
 fn load_atlases(
     store: &mut AssetStore,
     loaded_assets: &crate::LoadedAssets,
     texture_atlases: &mut Assets<TextureAtlas>,
+    sheets: &Assets<SpriteSheetAsset>,
 ) {
     for new_atlas in store.atlases_to_build.iter() {
         let texture_handle = store.get_handle(
@@ -59,6 +80,14 @@ fn load_atlases(
             .atlases
             .insert(new_atlas.tag.clone(), atlas_handle);
     }
+    for tag in store.sheets_to_build.iter() {
+        let atlas = store
+            .get_sprite_sheet(tag, loaded_assets, sheets)
+            .unwrap()
+            .atlas();
+        let atlas_handle = texture_atlases.add(atlas);
+        store.atlases.insert(tag.clone(), atlas_handle);
+    }
 }
 pub(crate) fn exit(
     mut commands: Commands,
@@ -2,6 +2,14 @@ mod asset_manager;
 pub use asset_manager::AssetManager;
 mod asset_store;
 pub use asset_store::*;
+mod weighted_table_asset;
+pub use weighted_table_asset::WeightedTableAsset;
+mod word_list_asset;
+pub use word_list_asset::WordListAsset;
+mod animations_asset;
+pub use animations_asset::AnimationsAsset;
+mod sprite_sheet_asset;
+pub use sprite_sheet_asset::{SpriteSheetAsset, SpriteSlice};
 mod loading_menu;
 pub(crate) use loading_menu::*;
 #[macro_export]
@@ -0,0 +1,496 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    reflect::TypePath,
+    utils::BoxedFuture,
+};
+use serde::{
+    de::{MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use std::{fmt, path::PathBuf};
+use crate::{AnimationFrame, AnimationOption, Animations, PerFrameAnimation};
+use super::animations_asset::AnimationSource;
+
+/// A named region of a sprite, such as a hitbox or an attachment point,
+/// exported from Aseprite's slices.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpriteSlice {
+    /// The slice's name in Aseprite.
+    pub name: String,
+    /// The first frame these bounds apply to. They apply until the next
+    /// key of the same slice.
+    pub frame: usize,
+    /// The slice's bounds, in pixels from the frame's top-left corner.
+    pub bounds: Rect,
+    /// The slice's pivot point, if it has one, relative to `bounds`.
+    pub pivot: Option<Vec2>,
+}
+
+/// A sprite sheet described by a `.sheet.json` file, loaded by
+/// [`AssetManager`](crate::AssetManager). Both Aseprite's and
+/// TexturePacker's JSON exports (in hash or array form) are supported.
+///
+/// The sheet becomes a [`TextureAtlas`] whose sprite indices follow the
+/// order of the frames in the file, and its animations are merged into
+/// the [`Animations`] resource:
+///
+/// * Aseprite's frame tags become animations of the same name, with each
+///   frame's duration and the tag's direction (forward, reverse or
+///   ping-pong). Tags with a repeat count (up to 1000) play that many
+///   times, counting each direction of a ping-pong as one, then hold
+///   their last frame; other tags loop.
+/// * Files without tags, like TexturePacker's, get one looping animation
+///   per run of numbered frames: `run_01.png`, `run_02.png`... become
+///   `"run"`. Frames without a duration last 100ms.
+///
+/// Rotated frames can't be represented in a `TextureAtlas`, so disable
+/// rotation when exporting. Trimmed frames are used as they are.
+#[derive(Asset, TypePath, Debug)]
+pub struct SpriteSheetAsset {
+    #[dependency]
+    image: Handle<Image>,
+    size: Vec2,
+    frames: Vec<Rect>,
+    animations: Animations,
+    slices: Vec<SpriteSlice>,
+}
+
+impl SpriteSheetAsset {
+    /// The sheet's image.
+    pub fn image(&self) -> &Handle<Image> {
+        &self.image
+    }
+
+    /// Each frame's area of the image, in sprite index order.
+    pub fn frames(&self) -> &[Rect] {
+        &self.frames
+    }
+
+    /// The animations described by the file.
+    pub fn animations(&self) -> &Animations {
+        &self.animations
+    }
+
+    /// Every key of every slice.
+    pub fn slices(&self) -> &[SpriteSlice] {
+        &self.slices
+    }
+
+    /// The slice called `name` as it is on `frame`, if it exists there.
+    pub fn slice(&self, name: &str, frame: usize) -> Option<&SpriteSlice> {
+        self.slices
+            .iter()
+            .filter(|slice| slice.name == name && slice.frame <= frame)
+            .max_by_key(|slice| slice.frame)
+    }
+
+    /// Builds the (non-uniform) texture atlas for the sheet.
+    pub fn atlas(&self) -> TextureAtlas {
+        let mut atlas = TextureAtlas::new_empty(self.image.clone(), self.size);
+        for frame in self.frames.iter() {
+            atlas.add_texture(*frame);
+        }
+        atlas
+    }
+}
+
+impl AnimationSource for SpriteSheetAsset {
+    fn animations(&self) -> &Animations {
+        &self.animations
+    }
+}
+
+#[derive(Deserialize)]
+struct SheetJson {
+    #[serde(deserialize_with = "frames_in_order")]
+    frames: Vec<(String, FrameJson)>,
+    meta: MetaJson,
+}
+
+#[derive(Deserialize)]
+struct FrameJson {
+    #[serde(default)]
+    filename: String,
+    frame: RectJson,
+    #[serde(default)]
+    rotated: bool,
+    duration: Option<u128>,
+}
+
+#[derive(Deserialize)]
+struct RectJson {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+impl From<&RectJson> for Rect {
+    fn from(rect: &RectJson) -> Self {
+        Rect::new(rect.x, rect.y, rect.x + rect.w, rect.y + rect.h)
+    }
+}
+
+#[derive(Deserialize)]
+struct SizeJson {
+    w: f32,
+    h: f32,
+}
+
+#[derive(Deserialize)]
+struct PointJson {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MetaJson {
+    image: String,
+    size: SizeJson,
+    #[serde(default)]
+    frame_tags: Vec<TagJson>,
+    #[serde(default)]
+    slices: Vec<SliceJson>,
+}
+
+#[derive(Deserialize)]
+struct TagJson {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: String,
+    repeat: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SliceJson {
+    name: String,
+    keys: Vec<SliceKeyJson>,
+}
+
+#[derive(Deserialize)]
+struct SliceKeyJson {
+    frame: usize,
+    bounds: RectJson,
+    pivot: Option<PointJson>,
+}
+
+// Frames come as an array, or as a map keyed by filename. A map's order
+// is the sprite order, so it can't go through a (sorted) map type.
+fn frames_in_order<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<(String, FrameJson)>, D::Error> {
+    struct FramesVisitor;
+    impl<'de> Visitor<'de> for FramesVisitor {
+        type Value = Vec<(String, FrameJson)>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an array or map of frames")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut frames = Vec::new();
+            while let Some(frame) = seq.next_element::<FrameJson>()? {
+                frames.push((frame.filename.clone(), frame));
+            }
+            Ok(frames)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut frames = Vec::new();
+            while let Some(entry) = map.next_entry::<String, FrameJson>()? {
+                frames.push(entry);
+            }
+            Ok(frames)
+        }
+    }
+    deserializer.deserialize_any(FramesVisitor)
+}
+
+/// A parsed sheet, before its image is loaded.
+#[derive(Debug)]
+pub(crate) struct ParsedSheet {
+    pub(crate) image: String,
+    pub(crate) size: Vec2,
+    pub(crate) frames: Vec<Rect>,
+    pub(crate) animations: Animations,
+    pub(crate) slices: Vec<SpriteSlice>,
+}
+
+const DEFAULT_DURATION_MS: u128 = 100;
+// Each repeat is unrolled into frames, so a huge count would exhaust
+// memory while loading
+const MAX_REPEAT: usize = 1000;
+
+pub(crate) fn parse_sprite_sheet(source: &str) -> anyhow::Result<ParsedSheet> {
+    let sheet: SheetJson = serde_json::from_str(source)?;
+    if sheet.frames.is_empty() {
+        anyhow::bail!("the sheet has no frames");
+    }
+    if let Some((name, _)) = sheet.frames.iter().find(|(_, frame)| frame.rotated) {
+        anyhow::bail!("frame {name} is rotated; export without rotation");
+    }
+    let durations: Vec<u128> = sheet
+        .frames
+        .iter()
+        .map(|(_, frame)| frame.duration.unwrap_or(DEFAULT_DURATION_MS))
+        .collect();
+
+    let mut animations = Animations::new();
+    if sheet.meta.frame_tags.is_empty() {
+        for (name, range) in numbered_runs(&sheet.frames) {
+            let sequence: Vec<usize> = range.collect();
+            animations.0.insert(name, tag_animation(&sequence, &durations, true));
+        }
+    }
+    for tag in sheet.meta.frame_tags.iter() {
+        if tag.from > tag.to || tag.to >= durations.len() {
+            anyhow::bail!(
+                "tag [{}] covers frames {}..={}, but there are only {} frames",
+                tag.name, tag.from, tag.to, durations.len()
+            );
+        }
+        let forward: Vec<usize> = (tag.from..=tag.to).collect();
+        let reverse: Vec<usize> = forward.iter().rev().copied().collect();
+        // The directions of odd and even passes through the tag
+        let (first, second, pingpong) = match tag.direction.as_str() {
+            "" | "forward" => (&forward, &forward, false),
+            "reverse" => (&reverse, &reverse, false),
+            "pingpong" => (&forward, &reverse, true),
+            "pingpong_reverse" => (&reverse, &forward, true),
+            other => anyhow::bail!("tag [{}] has unknown direction {other}", tag.name),
+        };
+        let passes = |count: usize| {
+            let mut sequence = first.clone();
+            for pass in 1..count {
+                let frames = if pass % 2 == 0 { first } else { second };
+                // Ping-pong doesn't show the frame it turns on twice
+                sequence.extend_from_slice(if pingpong { &frames[1..] } else { frames });
+            }
+            sequence
+        };
+        let repeat: Option<usize> = match &tag.repeat {
+            Some(count) => Some(count.parse().map_err(|_| {
+                anyhow::Error::msg(format!("tag [{}] has a bad repeat count {count}", tag.name))
+            })?),
+            None => None,
+        };
+        if let Some(count) = repeat.filter(|count| *count > MAX_REPEAT) {
+            anyhow::bail!(
+                "tag [{}] repeats {count} times; at most {MAX_REPEAT} are supported",
+                tag.name
+            );
+        }
+        let animation = match repeat {
+            // Aseprite counts each direction of a ping-pong as a pass
+            Some(count) if count > 0 => tag_animation(&passes(count), &durations, false),
+            // Aseprite writes 0 (or nothing) for "forever"
+            _ if pingpong => {
+                // There and back, leaving off the first frame the loop
+                // returns to
+                let mut sequence = passes(2);
+                if sequence.len() > 1 {
+                    sequence.pop();
+                }
+                tag_animation(&sequence, &durations, true)
+            }
+            _ => tag_animation(&passes(1), &durations, true),
+        };
+        animations.0.insert(tag.name.clone(), animation);
+    }
+
+    let mut slices = Vec::new();
+    for slice in sheet.meta.slices.iter() {
+        for key in slice.keys.iter() {
+            if key.frame >= durations.len() {
+                anyhow::bail!(
+                    "slice [{}] has a key on frame {}, but there are only {} frames",
+                    slice.name, key.frame, durations.len()
+                );
+            }
+            slices.push(SpriteSlice {
+                name: slice.name.clone(),
+                frame: key.frame,
+                bounds: Rect::from(&key.bounds),
+                pivot: key.pivot.as_ref().map(|p| Vec2::new(p.x, p.y)),
+            });
+        }
+    }
+
+    Ok(ParsedSheet {
+        image: sheet.meta.image,
+        size: Vec2::new(sheet.meta.size.w, sheet.meta.size.h),
+        frames: sheet.frames.iter().map(|(_, frame)| Rect::from(&frame.frame)).collect(),
+        animations,
+        slices,
+    })
+}
+
+// Groups consecutive frames whose names differ only by a trailing number
+// (and extension).
+fn numbered_runs(frames: &[(String, FrameJson)]) -> Vec<(String, std::ops::Range<usize>)> {
+    let stem = |name: &str| {
+        let name = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+        name.trim_end_matches(|c: char| c.is_ascii_digit())
+            .trim_end_matches(['_', '-', ' '])
+            .to_string()
+    };
+    let mut runs: Vec<(String, std::ops::Range<usize>)> = Vec::new();
+    for (index, (name, _)) in frames.iter().enumerate() {
+        let name = stem(name);
+        match runs.last_mut() {
+            Some((last, range)) if *last == name => range.end = index + 1,
+            _ => runs.push((name, index..index + 1)),
+        }
+    }
+    runs.retain(|(name, _)| !name.is_empty());
+    runs
+}
+
+fn tag_animation(sequence: &[usize], durations: &[u128], looping: bool) -> PerFrameAnimation {
+    let last = sequence.len() - 1;
+    PerFrameAnimation::new(
+        sequence
+            .iter()
+            .enumerate()
+            .map(|(position, sprite)| {
+                let action = match (position == last, looping) {
+                    (false, _) => vec![AnimationOption::NextFrame],
+                    (true, true) => vec![AnimationOption::GoToFrame(0)],
+                    // Hold the last frame
+                    (true, false) => Vec::new(),
+                };
+                AnimationFrame::new(*sprite, durations[*sprite], action)
+            })
+            .collect(),
+    )
+}
+
+#[derive(Default)]
+pub(crate) struct SpriteSheetLoader;
+
+impl AssetLoader for SpriteSheetLoader {
+    type Asset = SpriteSheetAsset;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut source = String::new();
+            reader.read_to_string(&mut source).await?;
+            let sheet = parse_sprite_sheet(&source).map_err(|e| {
+                anyhow::Error::msg(format!("{}: {e}", load_context.path().display()))
+            })?;
+            // The image is named relative to the JSON file
+            let image_path: PathBuf = match load_context.path().parent() {
+                Some(directory) => directory.join(&sheet.image),
+                None => PathBuf::from(&sheet.image),
+            };
+            Ok(SpriteSheetAsset {
+                image: load_context.load(image_path),
+                size: sheet.size,
+                frames: sheet.frames,
+                animations: sheet.animations,
+                slices: sheet.slices,
+            })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["sheet.json"]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ASEPRITE: &str = r##"{
+        "frames": {
+            "dragon 0.aseprite": { "frame": { "x": 0, "y": 0, "w": 62, "h": 65 }, "rotated": false, "trimmed": false, "duration": 66 },
+            "dragon 1.aseprite": { "frame": { "x": 62, "y": 0, "w": 62, "h": 65 }, "rotated": false, "trimmed": false, "duration": 66 },
+            "dragon 2.aseprite": { "frame": { "x": 124, "y": 0, "w": 60, "h": 65 }, "rotated": false, "trimmed": false, "duration": 500 },
+            "dragon 10.aseprite": { "frame": { "x": 184, "y": 0, "w": 62, "h": 60 }, "rotated": false, "trimmed": false, "duration": 500 }
+        },
+        "meta": {
+            "app": "http://www.aseprite.org/",
+            "image": "dragon.png",
+            "size": { "w": 246, "h": 65 },
+            "frameTags": [
+                { "name": "Flapping", "from": 0, "to": 3, "direction": "pingpong", "color": "#000000ff" },
+                { "name": "Glide", "from": 2, "to": 3, "direction": "reverse" },
+                { "name": "Crash", "from": 0, "to": 1, "direction": "forward", "repeat": "2" },
+                { "name": "Bounce", "from": 1, "to": 3, "direction": "pingpong", "repeat": "3" }
+            ],
+            "slices": [
+                { "name": "hitbox", "color": "#0000ffff", "keys": [
+                    { "frame": 0, "bounds": { "x": 4, "y": 8, "w": 50, "h": 40 } },
+                    { "frame": 2, "bounds": { "x": 6, "y": 8, "w": 46, "h": 40 }, "pivot": { "x": 23, "y": 20 } }
+                ] }
+            ]
+        }
+    }"##;
+
+    const TEXTURE_PACKER: &str = r#"{
+        "frames": [
+            { "filename": "run_01.png", "frame": { "x": 0, "y": 0, "w": 32, "h": 32 }, "rotated": false },
+            { "filename": "run_02.png", "frame": { "x": 32, "y": 0, "w": 32, "h": 32 }, "rotated": false },
+            { "filename": "jump.png", "frame": { "x": 0, "y": 32, "w": 32, "h": 40 }, "rotated": false }
+        ],
+        "meta": { "image": "hero.png", "size": { "w": 64, "h": 72 } }
+    }"#;
+
+    fn sprites(animation: &PerFrameAnimation) -> Vec<usize> {
+        animation.frames.iter().map(|frame| frame.sprite_index).collect()
+    }
+
+    #[test]
+    fn test_aseprite() {
+        let sheet = parse_sprite_sheet(ASEPRITE).unwrap();
+        assert_eq!(sheet.image, "dragon.png");
+        // File order, not sorted order ("dragon 10" is last)
+        assert_eq!(sheet.frames[3], Rect::new(184.0, 0.0, 246.0, 60.0));
+        assert_eq!(sprites(&sheet.animations.0["Flapping"]), vec![0, 1, 2, 3, 2, 1]);
+        assert_eq!(sprites(&sheet.animations.0["Glide"]), vec![3, 2]);
+        assert_eq!(sprites(&sheet.animations.0["Crash"]), vec![0, 1, 0, 1]);
+        assert_eq!(sprites(&sheet.animations.0["Bounce"]), vec![1, 2, 3, 2, 1, 2, 3]);
+        assert!(sheet.animations.0["Bounce"].frames[6].action.is_empty());
+        assert_eq!(sheet.animations.0["Glide"].frames[0].delay_ms, 500);
+        assert!(sheet.animations.0["Crash"].frames[3].action.is_empty());
+        sheet.animations.validate().unwrap();
+        assert_eq!(sheet.slices.len(), 2);
+        assert_eq!(sheet.slices[1].pivot, Some(Vec2::new(23.0, 20.0)));
+    }
+
+    #[test]
+    fn test_texture_packer() {
+        let sheet = parse_sprite_sheet(TEXTURE_PACKER).unwrap();
+        assert_eq!(sheet.size, Vec2::new(64.0, 72.0));
+        assert_eq!(sheet.frames.len(), 3);
+        assert_eq!(sprites(&sheet.animations.0["run"]), vec![0, 1]);
+        assert_eq!(sprites(&sheet.animations.0["jump"]), vec![2]);
+        assert_eq!(sheet.animations.0["run"].frames[0].delay_ms, DEFAULT_DURATION_MS);
+        sheet.animations.validate().unwrap();
+    }
+
+    #[test]
+    fn test_bad_sheets() {
+        let error = |source: &str| parse_sprite_sheet(source).unwrap_err().to_string();
+        assert!(error(&ASEPRITE.replace(r#""to": 3, "direction": "reverse""#, r#""to": 9, "direction": "reverse""#))
+            .contains("[Glide]"));
+        assert!(error(&ASEPRITE.replace(r#""repeat": "2""#, r#""repeat": "18446744073709551615""#))
+            .contains("repeats"));
+        assert!(error(&TEXTURE_PACKER.replacen(r#""rotated": false"#, r#""rotated": true"#, 1))
+            .contains("run_01.png"));
+        assert!(error(r#"{ "frames": [], "meta": { "image": "a.png", "size": { "w": 1, "h": 1 } } }"#)
+            .contains("no frames"));
+    }
+}
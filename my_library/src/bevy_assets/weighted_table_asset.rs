@@ -0,0 +1,58 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    reflect::TypePath,
+    utils::BoxedFuture,
+};
+use serde::de::DeserializeOwned;
+use crate::WeightedTable;
+
+/// The contents of a `.table.ron` file, loaded by [`AssetManager`](crate::AssetManager).
+///
+/// The value type isn't known until the table is used, so the asset keeps the
+/// (syntax checked) RON source and [`WeightedTableAsset::table`] builds the
+/// typed [`WeightedTable`] on request.
+#[derive(Asset, TypePath, Debug)]
+pub struct WeightedTableAsset {
+    source: String,
+}
+
+impl WeightedTableAsset {
+    /// Deserializes the table for value type `T`.
+    pub fn table<T: DeserializeOwned>(&self) -> anyhow::Result<WeightedTable<T>> {
+        Ok(ron::from_str(&self.source)?)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct WeightedTableLoader;
+
+impl AssetLoader for WeightedTableLoader {
+    type Asset = WeightedTableAsset;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut source = String::new();
+            reader.read_to_string(&mut source).await?;
+            // Catch syntax errors while loading, rather than mid-game
+            if let Err(e) = ron::from_str::<ron::Value>(&source) {
+                return Err(anyhow::Error::msg(format!(
+                    "{}: {e}",
+                    load_context.path().display()
+                )));
+            }
+            Ok(WeightedTableAsset { source })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["table.ron"]
+    }
+}
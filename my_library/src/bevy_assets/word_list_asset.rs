@@ -0,0 +1,70 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    reflect::TypePath,
+    utils::BoxedFuture,
+};
+use crate::Markov;
+
+/// The contents of a `.words.txt` file, loaded by [`AssetManager`](crate::AssetManager):
+/// one entry (a name, or a sentence) per line. Blank lines and lines
+/// starting with `#` are skipped.
+#[derive(Asset, TypePath, Debug)]
+pub struct WordListAsset {
+    entries: Vec<String>,
+}
+
+impl WordListAsset {
+    /// The entries in the list.
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Trains a letter-by-letter [`Markov`] name generator on the list.
+    pub fn names(&self, order: usize) -> Markov {
+        Markov::letters(order).trained(&self.entries)
+    }
+
+    /// Trains a word-by-word [`Markov`] text generator on the list.
+    pub fn text(&self, order: usize) -> Markov {
+        Markov::words(order).trained(&self.entries)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct WordListLoader;
+
+impl AssetLoader for WordListLoader {
+    type Asset = WordListAsset;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut source = String::new();
+            reader.read_to_string(&mut source).await?;
+            let entries: Vec<String> = source
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(String::from)
+                .collect();
+            if entries.is_empty() {
+                return Err(anyhow::Error::msg(format!(
+                    "{}: the word list is empty",
+                    load_context.path().display()
+                )));
+            }
+            Ok(WordListAsset { entries })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["words.txt"]
+    }
+}
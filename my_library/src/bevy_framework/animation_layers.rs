@@ -0,0 +1,227 @@
+use bevy::{prelude::*, utils::HashMap};
+use crate::AnimationCycle;
+
+/// Marks a child entity as one layer of its parent's animation. Spawn
+/// layers with [`spawn_animation_layer`].
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct AnimationLayer {
+    /// The layer's name, such as `"aura"` or `"hit_flash"`.
+    pub name: String,
+}
+
+/// `AnimationLayers` stacks several animations on one entity: a fire
+/// aura, a shield shimmer or a hit flash over the base sprite. Each
+/// layer is a child entity with its own `TextureAtlasSprite` and
+/// [`AnimationCycle`], so it keeps its own frame, speed and mode.
+///
+/// The parent controls the layers as a group: pausing it, or changing
+/// its speed, applies on top of each layer's own settings (and to the
+/// parent's own `AnimationCycle`, if it has one). Layers are children,
+/// so [`cleanup`](crate::cleanup) removes them along with the parent.
+///
+/// ## Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use my_library::*;
+///
+/// fn power_up(
+///     mut commands: Commands,
+///     assets: Res<AssetStore>,
+///     player: Query<Entity, With<Transform>>,
+/// ) {
+///     let player = player.single();
+///     let aura = assets.get_atlas_handle("fire").unwrap();
+///     spawn_animation_layer(&mut commands, player, "aura", aura, "Burning", -0.1);
+/// }
+///
+/// fn freeze(mut layers: Query<&mut AnimationLayers>) {
+///     layers.for_each_mut(|mut layers| layers.pause());
+/// }
+/// ```
+#[derive(Component, Clone, Debug)]
+pub struct AnimationLayers {
+    layers: HashMap<String, Entity>,
+    speed: f32,
+    paused: bool,
+}
+
+impl Default for AnimationLayers {
+    fn default() -> Self {
+        Self {
+            layers: HashMap::new(),
+            speed: 1.0,
+            paused: false,
+        }
+    }
+}
+
+impl AnimationLayers {
+    /// The entity holding the layer called `name`. Query it for its
+    /// `AnimationCycle` (to switch or pause just that layer) or its
+    /// `Visibility`. A layer that despawns itself at the end of a
+    /// one-shot leaves the group.
+    pub fn layer(&self, name: &str) -> Option<Entity> {
+        self.layers.get(name).copied()
+    }
+    // Removes a despawned layer
+    pub(crate) fn forget(&mut self, layer: Entity) {
+        self.layers.retain(|_, entity| *entity != layer);
+    }
+    /// The names of every layer.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.layers.keys().map(String::as_str)
+    }
+    /// Sets a speed that multiplies every layer's own speed.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.max(0.0);
+    }
+    /// The group's speed.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+    /// Freezes every layer.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+    /// Lets the layers play again. Layers paused individually stay
+    /// paused.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+    /// Is the group paused?
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+}
+
+/// Adds an animation layer called `name` to `parent`, playing
+/// `animation` from `atlas`. `z` places the layer in front of (positive)
+/// or behind (negative) the parent. Replacing an existing layer of the
+/// same name despawns the old one. Returns the layer's entity.
+pub fn spawn_animation_layer(
+    commands: &mut Commands,
+    parent: Entity,
+    name: impl ToString,
+    atlas: Handle<TextureAtlas>,
+    animation: impl ToString,
+    z: f32,
+) -> Entity {
+    let name = name.to_string();
+    let layer = commands
+        .spawn(SpriteSheetBundle {
+            texture_atlas: atlas,
+            transform: Transform::from_xyz(0.0, 0.0, z),
+            ..default()
+        })
+        .insert(AnimationCycle::new(animation))
+        .insert(AnimationLayer { name: name.clone() })
+        .id();
+    commands.entity(parent).add_child(layer);
+    commands.add(move |world: &mut World| {
+        let Some(mut parent) = world.get_entity_mut(parent) else {
+            return;
+        };
+        let replaced = match parent.get_mut::<AnimationLayers>() {
+            Some(mut layers) => layers.layers.insert(name, layer),
+            None => {
+                let mut layers = AnimationLayers::default();
+                layers.layers.insert(name, layer);
+                parent.insert(layers);
+                None
+            }
+        };
+        if let Some(old) = replaced {
+            despawn_with_children_recursive(world, old);
+        }
+    });
+    layer
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::ecs::system::CommandQueue;
+    use crate::{cleanup, AnimationFrame, AnimationOption, Animations, OneShotEnd, PerFrameAnimation};
+    use super::super::bevy_animation::test::{advance, headless};
+
+    #[derive(Component)]
+    struct Player;
+
+    fn spin() -> Animations {
+        Animations::new()
+            .with_animation("spin", PerFrameAnimation::new(vec![
+                AnimationFrame::new(0, 100, vec![AnimationOption::NextFrame]),
+                AnimationFrame::new(1, 100, vec![AnimationOption::NextFrame]),
+                AnimationFrame::new(2, 100, vec![AnimationOption::GoToFrame(0)]),
+            ]))
+            .unwrap()
+    }
+
+    fn spawn_player(app: &mut App) -> (Entity, Entity, Entity) {
+        let player = app.world
+            .spawn((Player, AnimationCycle::new("spin"), TextureAtlasSprite::default(), SpatialBundle::default()))
+            .id();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &app.world);
+        let aura = spawn_animation_layer(&mut commands, player, "aura", default(), "spin", -0.1);
+        let shield = spawn_animation_layer(&mut commands, player, "shield".to_string(), default(), "spin", 0.1);
+        queue.apply(&mut app.world);
+        (player, aura, shield)
+    }
+
+    fn frame(app: &App, entity: Entity) -> usize {
+        app.world.get::<AnimationCycle>(entity).unwrap().frame()
+    }
+
+    #[test]
+    fn test_layers_play_independently_and_as_a_group() {
+        let mut app = headless(spin());
+        let (player, aura, shield) = spawn_player(&mut app);
+        let layers = app.world.get::<AnimationLayers>(player).unwrap();
+        assert_eq!(layers.layer("aura"), Some(aura));
+        assert_eq!(layers.layer("shield"), Some(shield));
+
+        app.world.get_mut::<AnimationCycle>(shield).unwrap().set_speed(2.0);
+        advance(&mut app, 0);
+        advance(&mut app, 100);
+        assert_eq!((frame(&app, player), frame(&app, aura), frame(&app, shield)), (1, 1, 2));
+
+        app.world.get_mut::<AnimationLayers>(player).unwrap().pause();
+        advance(&mut app, 100);
+        assert_eq!((frame(&app, player), frame(&app, aura), frame(&app, shield)), (1, 1, 2));
+
+        let mut layers = app.world.get_mut::<AnimationLayers>(player).unwrap();
+        layers.resume();
+        layers.set_speed(2.0);
+        advance(&mut app, 50);
+        assert_eq!((frame(&app, player), frame(&app, aura), frame(&app, shield)), (2, 2, 1));
+    }
+
+    #[test]
+    fn test_cleanup_removes_layers() {
+        let mut app = headless(spin());
+        let (player, aura, shield) = spawn_player(&mut app);
+        app.add_systems(Last, cleanup::<Player>);
+        advance(&mut app, 0);
+        for entity in [player, aura, shield] {
+            assert!(app.world.get_entity(entity).is_none());
+        }
+    }
+    #[test]
+    fn test_despawned_layer_leaves_group() {
+        let animations = spin()
+            .with_animation("pop", PerFrameAnimation::new(vec![AnimationFrame::new(3, 100, Vec::new())]))
+            .unwrap();
+        let mut app = headless(animations);
+        let (player, aura, shield) = spawn_player(&mut app);
+        app.world.get_mut::<AnimationCycle>(aura).unwrap().play_once("pop", OneShotEnd::Despawn);
+        advance(&mut app, 0);
+        advance(&mut app, 100);
+        assert!(app.world.get_entity(aura).is_none());
+        let layers = app.world.get::<AnimationLayers>(player).unwrap();
+        assert_eq!(layers.layer("aura"), None);
+        assert_eq!(layers.names().collect::<Vec<_>>(), vec!["shield"]);
+        assert_eq!(layers.layer("shield"), Some(shield));
+    }
+}
@@ -0,0 +1,409 @@
+use bevy::{prelude::*, utils::HashMap, log};
+use crate::{AnimationCycle, Animations};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Parameter {
+    Bool(bool),
+    Float(f32),
+    Trigger(bool),
+}
+
+/// A test on an [`AnimationStateMachine`]'s parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnimationCondition {
+    /// The bool parameter is true.
+    IsTrue(String),
+    /// The bool parameter is false.
+    IsFalse(String),
+    /// The float parameter is greater than the value.
+    Above(String, f32),
+    /// The float parameter is less than the value.
+    Below(String, f32),
+    /// The trigger is set. Triggers stay set until a transition that
+    /// tests them fires.
+    Triggered(String),
+}
+
+impl AnimationCondition {
+    /// The bool parameter `name` is true.
+    pub fn is_true<S: ToString>(name: S) -> Self {
+        Self::IsTrue(name.to_string())
+    }
+    /// The bool parameter `name` is false.
+    pub fn is_false<S: ToString>(name: S) -> Self {
+        Self::IsFalse(name.to_string())
+    }
+    /// The float parameter `name` is greater than `value`.
+    pub fn above<S: ToString>(name: S, value: f32) -> Self {
+        Self::Above(name.to_string(), value)
+    }
+    /// The float parameter `name` is less than `value`.
+    pub fn below<S: ToString>(name: S, value: f32) -> Self {
+        Self::Below(name.to_string(), value)
+    }
+    /// The trigger `name` is set.
+    pub fn triggered<S: ToString>(name: S) -> Self {
+        Self::Triggered(name.to_string())
+    }
+
+    fn parameter(&self) -> &str {
+        match self {
+            AnimationCondition::IsTrue(name) | AnimationCondition::IsFalse(name) | AnimationCondition::Above(name, _)
+            | AnimationCondition::Below(name, _) | AnimationCondition::Triggered(name) => name,
+        }
+    }
+}
+
+/// A rule for moving between states of an [`AnimationStateMachine`].
+/// It fires when every condition holds and the exit time has passed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationTransition {
+    from: Option<String>,
+    to: String,
+    conditions: Vec<AnimationCondition>,
+    exit_time: Option<f32>,
+}
+
+impl AnimationTransition {
+    /// A transition from the state `from` to the state `to`.
+    pub fn new<S: ToString>(from: S, to: S) -> Self {
+        Self {
+            from: Some(from.to_string()),
+            to: to.to_string(),
+            conditions: Vec::new(),
+            exit_time: None,
+        }
+    }
+    /// A transition from any other state to `to`, such as "dead".
+    pub fn from_any<S: ToString>(to: S) -> Self {
+        Self {
+            from: None,
+            to: to.to_string(),
+            conditions: Vec::new(),
+            exit_time: None,
+        }
+    }
+    /// Adds a condition. All of them must hold for the transition to
+    /// fire.
+    pub fn when(mut self, condition: AnimationCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+    /// Only fires once the current state's animation has played for
+    /// `exit_time` times its length: 1.0 waits for a full pass, 0.5 for
+    /// half of one.
+    pub fn after(mut self, exit_time: f32) -> Self {
+        self.exit_time = Some(exit_time);
+        self
+    }
+}
+
+/// `AnimationStateMachine` picks an entity's animation from named
+/// states, so gameplay only sets parameters (`vertical_speed`, `dead`)
+/// rather than switching animations itself.
+///
+/// Each state plays an animation from the [`Animations`] resource.
+/// Every update, [`animation_state_machines`] fires the first transition
+/// (in the order they were added) whose conditions hold, and switches the
+/// entity's [`AnimationCycle`] to the new state's animation.
+///
+/// ## Example
+///
+/// ```
+/// use my_library::{AnimationStateMachine, AnimationCondition, AnimationTransition};
+/// let mut machine = AnimationStateMachine::new("gliding")
+///     .with_state("gliding", "Straight and Level")
+///     .with_state("flapping", "Flapping")
+///     .with_float("vertical_speed", 0.0)
+///     .with_transition(AnimationTransition::new("gliding", "flapping")
+///         .when(AnimationCondition::above("vertical_speed", 0.0)))
+///     .with_transition(AnimationTransition::new("flapping", "gliding")
+///         .when(AnimationCondition::below("vertical_speed", 0.0))
+///         .after(1.0));
+/// machine.validate().unwrap();
+/// machine.set_float("vertical_speed", 2.0);
+/// ```
+#[derive(Component, Clone, Debug)]
+pub struct AnimationStateMachine {
+    states: HashMap<String, String>,
+    transitions: Vec<AnimationTransition>,
+    parameters: HashMap<String, Parameter>,
+    current: String,
+    time_in_state: f32,
+    entered: bool,
+}
+
+impl AnimationStateMachine {
+    /// Creates a state machine that starts in `initial`.
+    pub fn new<S: ToString>(initial: S) -> Self {
+        Self {
+            states: HashMap::new(),
+            transitions: Vec::new(),
+            parameters: HashMap::new(),
+            current: initial.to_string(),
+            time_in_state: 0.0,
+            entered: false,
+        }
+    }
+    /// Adds a state that plays the animation tagged `animation`.
+    pub fn with_state<S: ToString>(mut self, state: S, animation: S) -> Self {
+        self.states.insert(state.to_string(), animation.to_string());
+        self
+    }
+    /// Adds a bool parameter.
+    pub fn with_bool<S: ToString>(mut self, name: S, value: bool) -> Self {
+        self.parameters.insert(name.to_string(), Parameter::Bool(value));
+        self
+    }
+    /// Adds a float parameter.
+    pub fn with_float<S: ToString>(mut self, name: S, value: f32) -> Self {
+        self.parameters.insert(name.to_string(), Parameter::Float(value));
+        self
+    }
+    /// Adds a trigger: a parameter that is set once, and cleared by the
+    /// transition that uses it.
+    pub fn with_trigger<S: ToString>(mut self, name: S) -> Self {
+        self.parameters.insert(name.to_string(), Parameter::Trigger(false));
+        self
+    }
+    /// Adds a transition. Transitions are tried in the order they were
+    /// added.
+    pub fn with_transition(mut self, transition: AnimationTransition) -> Self {
+        self.transitions.push(transition);
+        self
+    }
+
+    /// Checks that every state and parameter the machine refers to
+    /// exists, and that conditions test parameters of the right type.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !self.states.contains_key(&self.current) {
+            anyhow::bail!("initial state [{}] is not a state", self.current);
+        }
+        for transition in self.transitions.iter() {
+            for state in transition.from.iter().chain(std::iter::once(&transition.to)) {
+                if !self.states.contains_key(state) {
+                    anyhow::bail!("transition to [{}]: [{state}] is not a state", transition.to);
+                }
+            }
+            for condition in transition.conditions.iter() {
+                let name = condition.parameter();
+                let matches = match (condition, self.parameters.get(name)) {
+                    (AnimationCondition::IsTrue(_) | AnimationCondition::IsFalse(_), Some(Parameter::Bool(_))) => true,
+                    (AnimationCondition::Above(..) | AnimationCondition::Below(..), Some(Parameter::Float(_))) => true,
+                    (AnimationCondition::Triggered(_), Some(Parameter::Trigger(_))) => true,
+                    (_, None) => anyhow::bail!(
+                        "transition to [{}]: [{name}] is not a parameter",
+                        transition.to
+                    ),
+                    _ => false,
+                };
+                if !matches {
+                    anyhow::bail!(
+                        "transition to [{}]: {condition:?} tests the wrong type of parameter",
+                        transition.to
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn set(&mut self, name: &str, value: Parameter) {
+        match self.parameters.get_mut(name) {
+            Some(parameter) if std::mem::discriminant(parameter) == std::mem::discriminant(&value) => {
+                *parameter = value;
+            }
+            _ => log::warn!("Animation parameter [{name}] is not a {value:?}"),
+        }
+    }
+    /// Sets a bool parameter.
+    pub fn set_bool(&mut self, name: &str, value: bool) {
+        self.set(name, Parameter::Bool(value));
+    }
+    /// Sets a float parameter.
+    pub fn set_float(&mut self, name: &str, value: f32) {
+        self.set(name, Parameter::Float(value));
+    }
+    /// Sets a trigger.
+    pub fn trigger(&mut self, name: &str) {
+        self.set(name, Parameter::Trigger(true));
+    }
+    /// The value of a bool parameter, if there is one called `name`.
+    pub fn bool(&self, name: &str) -> Option<bool> {
+        match self.parameters.get(name) {
+            Some(Parameter::Bool(value)) => Some(*value),
+            _ => None,
+        }
+    }
+    /// The value of a float parameter, if there is one called `name`.
+    pub fn float(&self, name: &str) -> Option<f32> {
+        match self.parameters.get(name) {
+            Some(Parameter::Float(value)) => Some(*value),
+            _ => None,
+        }
+    }
+    /// The current state.
+    pub fn state(&self) -> &str {
+        &self.current
+    }
+
+    fn holds(&self, condition: &AnimationCondition) -> bool {
+        let parameter = self.parameters.get(condition.parameter());
+        match (condition, parameter) {
+            (AnimationCondition::IsTrue(_), Some(Parameter::Bool(value))) => *value,
+            (AnimationCondition::IsFalse(_), Some(Parameter::Bool(value))) => !*value,
+            (AnimationCondition::Above(_, limit), Some(Parameter::Float(value))) => value > limit,
+            (AnimationCondition::Below(_, limit), Some(Parameter::Float(value))) => value < limit,
+            (AnimationCondition::Triggered(_), Some(Parameter::Trigger(set))) => *set,
+            _ => false,
+        }
+    }
+
+    // The first transition that can fire now
+    fn ready_transition(&self, animations: &Animations) -> Option<usize> {
+        let length_ms: f32 = self
+            .states
+            .get(&self.current)
+            .and_then(|tag| animations.0.get(tag))
+            .map_or(0.0, |animation| animation.frames.iter().map(|f| f.delay_ms as f32).sum());
+        self.transitions.iter().position(|transition| {
+            let from_here = match &transition.from {
+                Some(from) => *from == self.current,
+                None => transition.to != self.current,
+            };
+            from_here
+                && !matches!(transition.exit_time, Some(exit) if self.time_in_state < exit * length_ms)
+                && transition.conditions.iter().all(|c| self.holds(c))
+        })
+    }
+
+    fn enter(&mut self, state: String, cycle: &mut AnimationCycle) {
+        match self.states.get(&state) {
+            Some(animation) => cycle.switch(animation),
+            None => log::warn!("Animation state [{state}] not found!"),
+        }
+        self.current = state;
+        self.time_in_state = 0.0;
+    }
+
+    fn update(&mut self, ms: f32, cycle: &mut AnimationCycle, animations: &Animations) {
+        if !self.entered {
+            self.entered = true;
+            self.enter(self.current.clone(), cycle);
+        } else if !cycle.is_paused() {
+            // Exit times follow the animation, so they scale with its speed
+            self.time_in_state += ms * cycle.speed();
+        }
+        if let Some(index) = self.ready_transition(animations) {
+            let transition = self.transitions[index].clone();
+            for condition in transition.conditions.iter() {
+                if let AnimationCondition::Triggered(name) = condition {
+                    self.parameters.insert(name.clone(), Parameter::Trigger(false));
+                }
+            }
+            self.enter(transition.to, cycle);
+        }
+    }
+}
+
+/// Runs every [`AnimationStateMachine`], switching its entity's
+/// [`AnimationCycle`] when a transition fires. Schedule it before
+/// [`cycle_animations`](crate::cycle_animations).
+pub fn animation_state_machines(
+    animations: Res<Animations>,
+    mut machines: Query<(&mut AnimationStateMachine, &mut AnimationCycle)>,
+    time: Res<Time>,
+) {
+    let ms_since_last_call = time.delta_seconds() * 1000.0;
+    machines.for_each_mut(|(mut machine, mut cycle)| {
+        machine.update(ms_since_last_call, &mut cycle, &animations);
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AnimationFrame, AnimationOption, PerFrameAnimation};
+
+    fn animations() -> Animations {
+        let looping = || PerFrameAnimation::new(vec![
+            AnimationFrame::new(0, 100, vec![AnimationOption::NextFrame]),
+            AnimationFrame::new(1, 100, vec![AnimationOption::GoToFrame(0)]),
+        ]);
+        Animations::new()
+            .with_animation("idle", looping())
+            .and_then(|a| a.with_animation("jump", looping()))
+            .and_then(|a| a.with_animation("die", looping()))
+            .unwrap()
+    }
+
+    fn machine() -> AnimationStateMachine {
+        AnimationStateMachine::new("idle")
+            .with_state("idle", "idle")
+            .with_state("jump", "jump")
+            .with_state("dead", "die")
+            .with_float("vertical_speed", 0.0)
+            .with_bool("dead", false)
+            .with_trigger("jump")
+            .with_transition(AnimationTransition::from_any("dead").when(AnimationCondition::is_true("dead")))
+            .with_transition(AnimationTransition::new("idle", "jump").when(AnimationCondition::triggered("jump")))
+            .with_transition(AnimationTransition::new("jump", "idle")
+                .when(AnimationCondition::below("vertical_speed", 0.1))
+                .after(1.0))
+    }
+
+    fn run(machine: &mut AnimationStateMachine, cycle: &mut AnimationCycle, ms: f32) {
+        machine.update(ms, cycle, &animations());
+    }
+
+    #[test]
+    fn test_validate() {
+        machine().validate().unwrap();
+        assert!(machine().with_transition(AnimationTransition::new("idle", "swim")).validate().is_err());
+        assert!(machine()
+            .with_transition(AnimationTransition::new("idle", "jump").when(AnimationCondition::is_true("jump")))
+            .validate()
+            .is_err());
+        assert!(machine()
+            .with_transition(AnimationTransition::new("idle", "jump").when(AnimationCondition::above("speed", 1.0)))
+            .validate()
+            .is_err());
+        assert!(AnimationStateMachine::new("nowhere").validate().is_err());
+    }
+
+    #[test]
+    fn test_transitions() {
+        let mut machine = machine();
+        let mut cycle = AnimationCycle::new("idle");
+        run(&mut machine, &mut cycle, 16.0);
+        assert_eq!(machine.state(), "idle");
+        machine.trigger("jump");
+        machine.set_float("vertical_speed", 5.0);
+        run(&mut machine, &mut cycle, 16.0);
+        assert_eq!((machine.state(), cycle.tag()), ("jump", "jump"));
+        // The trigger was used up
+        assert!(!machine.holds(&AnimationCondition::triggered("jump")));
+        // Landing waits for the jump animation to play through
+        machine.set_float("vertical_speed", 0.0);
+        run(&mut machine, &mut cycle, 150.0);
+        assert_eq!(machine.state(), "jump");
+        run(&mut machine, &mut cycle, 50.0);
+        assert_eq!(machine.state(), "idle");
+        machine.set_bool("dead", true);
+        run(&mut machine, &mut cycle, 16.0);
+        assert_eq!((machine.state(), cycle.tag()), ("dead", "die"));
+        // "Any state" doesn't re-enter the state it leads to
+        machine.time_in_state = 0.0;
+        run(&mut machine, &mut cycle, 16.0);
+        assert_eq!(machine.time_in_state, 16.0);
+    }
+
+    #[test]
+    fn test_wrong_parameter_type_is_ignored() {
+        let mut machine = machine();
+        machine.set_bool("vertical_speed", true);
+        machine.set_float("missing", 1.0);
+        assert_eq!(machine.float("vertical_speed"), Some(0.0));
+        assert_eq!(machine.bool("dead"), Some(false));
+    }
+}
@@ -1,17 +1,38 @@
 use bevy::{prelude::*, utils::HashMap, log};
+use serde::{Deserialize, Serialize};
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum AnimationOption {
     None,
     NextFrame,
     GoToFrame(usize),
     SwitchToAnimation(String),
     PlaySound(String),
+    /// Sends an [`AnimationEvent`] with this name, so gameplay can react
+    /// to a keyframe---spawning a fireball, or enabling a hitbox.
+    ///
+    /// Unlike the other actions, which run once the frame's delay is
+    /// over, the event is sent as soon as the frame is shown.
+    Emit(String),
 }
 
+/// Sent by [`cycle_animations`] when a frame with an
+/// [`AnimationOption::Emit`] action becomes the current frame. Read it
+/// with `EventReader<AnimationEvent>`.
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct AnimationEvent {
+    /// The animated entity.
+    pub entity: Entity,
+    /// The name given to `Emit`.
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AnimationFrame {
-    sprite_index: usize,
-    delay_ms: u128,
-    action: Vec<AnimationOption>,
+    pub(crate) sprite_index: usize,
+    pub(crate) delay_ms: u128,
+    #[serde(default)]
+    pub(crate) action: Vec<AnimationOption>,
 }
 impl AnimationFrame {
     pub fn new(
@@ -22,6 +43,8 @@ impl AnimationFrame {
         Self { sprite_index, delay_ms, action }
     }
 }
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct PerFrameAnimation {
     pub frames: Vec<AnimationFrame>,
 }
@@ -30,91 +53,422 @@ impl PerFrameAnimation {
         Self { frames }
     }
 }
-#[derive(Resource)]
-pub struct Animations(HashMap<String, PerFrameAnimation>);
+#[derive(Resource, Clone, Debug, Default)]
+pub struct Animations(pub(crate) HashMap<String, PerFrameAnimation>);
 impl Animations {
     pub fn new() -> Self {
         Self(HashMap::new())
     }
+    /// Checks that every frame reference points somewhere real:
+    /// `GoToFrame` and `NextFrame` must land on a frame of the same
+    /// animation, and `SwitchToAnimation` must name a known animation.
+    /// Returns an error describing the first bad reference found.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let mut tags: Vec<&String> = self.0.keys().collect();
+        tags.sort();
+        for tag in tags {
+            Self::validate_animation(tag, &self.0[tag], |new| self.0.contains_key(new))?;
+        }
+        Ok(())
+    }
+    fn validate_animation(
+        tag: &str,
+        animation: &PerFrameAnimation,
+        known: impl Fn(&str) -> bool,
+    ) -> anyhow::Result<()> {
+        let frames = &animation.frames;
+        if frames.is_empty() {
+            anyhow::bail!("animation [{tag}] has no frames");
+        }
+        for (index, frame) in frames.iter().enumerate() {
+            for action in frame.action.iter() {
+                match action {
+                    AnimationOption::NextFrame if index + 1 >= frames.len() => {
+                        anyhow::bail!(
+                            "animation [{tag}] frame {index}: NextFrame runs past the last frame"
+                        );
+                    }
+                    AnimationOption::GoToFrame(target) if *target >= frames.len() => {
+                        anyhow::bail!(
+                            "animation [{tag}] frame {index}: GoToFrame({target}) but there are only {} frames",
+                            frames.len()
+                        );
+                    }
+                    AnimationOption::SwitchToAnimation(new) if !known(new) => {
+                        anyhow::bail!(
+                            "animation [{tag}] frame {index}: SwitchToAnimation(\"{new}\") is not a known animation"
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Adds an animation, checking its frame references first (see
+    /// [`Animations::validate`]). An animation can only switch to itself
+    /// or to animations added before it.
     pub fn with_animation<S: ToString>(
         mut self,
         tag: S,
         animation: PerFrameAnimation,
-    ) -> Self
+    ) -> anyhow::Result<Self>
     {
+        let tag = tag.to_string();
+        Self::validate_animation(&tag, &animation, |new| {
+            new == tag || self.0.contains_key(new)
+        })?;
         self.0.insert(
-            tag.to_string(),
+            tag,
             animation
         );
-        self
+        Ok(self)
     }
 }
+/// How an [`AnimationCycle`] moves through its frames.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlaybackMode {
+    /// Follow each frame's `NextFrame` and `GoToFrame` actions.
+    #[default]
+    Forward,
+    /// Step backwards through the frames, wrapping from the first to the
+    /// last. Frame actions other than `NextFrame` and `GoToFrame` still
+    /// run.
+    Reverse,
+    /// Step forwards to the last frame, then back to the first, and
+    /// repeat. Frame actions other than `NextFrame` and `GoToFrame`
+    /// still run.
+    PingPong,
+}
+
+/// What happens when an animation started with
+/// [`AnimationCycle::play_once`] reaches its end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OneShotEnd {
+    /// Stay on the last frame.
+    Hold,
+    /// Despawn the entity (and its children)---for explosions and other
+    /// effects that clean up after themselves.
+    Despawn,
+    /// Go back to the animation that was playing before.
+    Return,
+}
+
+#[derive(Clone, Debug)]
+struct OneShot {
+    end: OneShotEnd,
+    previous: String,
+}
+
 #[derive(Component)]
 pub struct AnimationCycle {
     animation_tag: String,
     current_frame: usize,
-    timer: u128,
+    timer: f32,
+    speed: f32,
+    paused: bool,
+    mode: PlaybackMode,
+    // Ping-pong is on its way back to the first frame
+    returning: bool,
+    // Start from the mode's first frame on the next update
+    restart: bool,
+    // The current frame's events have been sent
+    emitted: bool,
+    one_shot: Option<OneShot>,
+    finished: bool,
 }
 impl AnimationCycle {
     pub fn new<S: ToString>(tag: S) -> Self {
         Self {
             animation_tag: tag.to_string(),
             current_frame: 0,
-            timer: 0,
+            timer: 0.0,
+            speed: 1.0,
+            paused: false,
+            mode: PlaybackMode::Forward,
+            returning: false,
+            restart: true,
+            emitted: false,
+            one_shot: None,
+            finished: false,
         }
     }
+    /// Sets the playback speed, and returns the cycle.
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.set_speed(speed);
+        self
+    }
+    /// Sets the playback mode, and returns the cycle.
+    pub fn with_mode(mut self, mode: PlaybackMode) -> Self {
+        self.set_mode(mode);
+        self
+    }
+    /// Switches to another animation, cancelling any one-shot.
     pub fn switch<S: ToString>(&mut self, new: S) {
         let new = new.to_string();
-        if new != self.animation_tag {
-            self.animation_tag = new;
+        if new != self.animation_tag || self.finished {
+            self.timer = 0.0;
+            self.start(new);
+        }
+        self.one_shot = None;
+    }
+    /// Plays `tag` through once, then ends as `end` says. Does nothing
+    /// if `tag` is already playing once.
+    pub fn play_once<S: ToString>(&mut self, tag: S, end: OneShotEnd) {
+        let tag = tag.to_string();
+        if tag == self.animation_tag && self.one_shot.is_some() && !self.finished {
+            return;
+        }
+        let previous = match &self.one_shot {
+            // Return to whatever was playing before the first one-shot
+            Some(one_shot) if !self.finished => one_shot.previous.clone(),
+            _ => self.animation_tag.clone(),
+        };
+        self.one_shot = Some(OneShot { end, previous });
+        self.timer = 0.0;
+        self.start(tag);
+    }
+    // Leaves the timer alone, so time left over from the previous
+    // animation carries into the new one
+    fn start(&mut self, tag: String) {
+        self.animation_tag = tag;
+        self.current_frame = 0;
+        self.restart = true;
+        self.emitted = false;
+        self.finished = false;
+    }
+    /// The tag of the animation that is playing.
+    pub fn tag(&self) -> &str {
+        &self.animation_tag
+    }
+    /// The index of the current frame within the animation.
+    pub fn frame(&self) -> usize {
+        self.current_frame
+    }
+    /// Sets the playback speed: 2.0 plays twice as fast, 0.5 at half
+    /// speed. Negative speeds are treated as 0.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.max(0.0);
+    }
+    /// The playback speed.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+    /// Freezes the animation on its current frame.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+    /// Continues a paused animation.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+    /// Is the animation paused?
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+    /// Changes how the animation moves through its frames, from the
+    /// current frame.
+    pub fn set_mode(&mut self, mode: PlaybackMode) {
+        self.mode = mode;
+        self.returning = false;
+    }
+    /// The playback mode.
+    pub fn mode(&self) -> PlaybackMode {
+        self.mode
+    }
+    /// Has a one-shot animation finished, holding its last frame?
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+    // Picks the mode's first frame after a switch
+    fn begin(&mut self, frames: usize) {
+        if self.restart {
+            self.restart = false;
+            self.returning = false;
+            self.current_frame = match self.mode {
+                PlaybackMode::Reverse => frames - 1,
+                _ => 0,
+            };
+        }
+        // A hot reload may have shortened the animation
+        if self.current_frame >= frames {
             self.current_frame = 0;
-            self.timer = 0;
+        }
+    }
+    // Is the current frame the last one the mode will play?
+    fn at_end(&self, frames: usize) -> bool {
+        match self.mode {
+            PlaybackMode::Forward => self.current_frame + 1 >= frames,
+            PlaybackMode::Reverse => self.current_frame == 0,
+            PlaybackMode::PingPong => frames == 1 || (self.returning && self.current_frame == 0),
+        }
+    }
+    // Moves on a frame for the reverse and ping-pong modes
+    fn step(&mut self, frames: usize) {
+        let last = frames - 1;
+        self.emitted = false;
+        match self.mode {
+            PlaybackMode::Forward => {}
+            PlaybackMode::Reverse => {
+                self.current_frame = if self.current_frame == 0 { last } else { self.current_frame - 1 };
+            }
+            PlaybackMode::PingPong => {
+                if !self.returning && self.current_frame >= last {
+                    self.returning = true;
+                } else if self.returning && self.current_frame == 0 {
+                    self.returning = false;
+                }
+                self.current_frame = match self.returning {
+                    false => (self.current_frame + 1).min(last),
+                    true => self.current_frame.saturating_sub(1),
+                };
+            }
         }
     }
 }
+// Stops a run of zero-delay frames from looping forever
+const MAX_FRAMES_PER_UPDATE: usize = 100;
+
+#[allow(clippy::too_many_arguments)]
 pub fn cycle_animations(
     animations: Res<Animations>,
-    mut animated: Query<(&mut AnimationCycle, &mut TextureAtlasSprite)>,
+    mut animated: Query<(Entity, &mut AnimationCycle, &mut TextureAtlasSprite, Option<&Parent>)>,
+    groups: Query<&crate::AnimationLayers>,
     time: Res<Time>,
     assets: Res<crate::AssetStore>,
     mut commands: Commands,
     loaded_assets: Res<crate::LoadedAssets>,
+    mut events: EventWriter<AnimationEvent>,
 ) {
-    let ms_since_last_call = time.delta().as_millis();
-    animated.for_each_mut(|(mut animation, mut sprite)| {
-        animation.timer += ms_since_last_call;
-        if let Some(cycle) = animations.0.get(&animation.animation_tag) {
+    let ms_since_last_call = time.delta_seconds() * 1000.0;
+    animated.for_each_mut(|(entity, mut animation, mut sprite, parent)| {
+        // Layers follow their parent's group settings, as does the
+        // parent's own animation
+        let (group_speed, group_paused) = groups
+            .get(entity)
+            .ok()
+            .or_else(|| parent.and_then(|parent| groups.get(parent.get()).ok()))
+            .map_or((1.0, false), |group| (group.speed(), group.is_paused()));
+        if animation.paused || group_paused || animation.finished {
+            return;
+        }
+        animation.timer += ms_since_last_call * animation.speed * group_speed;
+        // A slow update may have covered several frames: play each in
+        // turn, carrying the time left over into the next
+        let mut steps = 0;
+        loop {
+            if animation.finished {
+                break;
+            }
+            if steps == MAX_FRAMES_PER_UPDATE {
+                animation.timer = 0.0;
+                break;
+            }
+            steps += 1;
+            let Some(cycle) = animations.0.get(&animation.animation_tag) else {
+                log::warn ! ("Animation Cycle [{}] not found!",
+                animation.animation_tag);
+                return;
+            };
+            let frames = cycle.frames.len();
+            if frames == 0 {
+                return;
+            }
+            animation.begin(frames);
             let current_frame = &cycle.frames[animation.current_frame];
-            if animation.timer > current_frame.delay_ms {
-                animation.timer = 0;
+            if !animation.emitted {
+                animation.emitted = true;
                 for action in current_frame.action.iter() {
-                    match action {
-                        AnimationOption::None => {},
-                        AnimationOption::NextFrame => {
-                            animation.current_frame += 1;
-                        }
-                        AnimationOption::GoToFrame(frame) => {
-                            animation.current_frame = *frame;
-                        }
-                        AnimationOption::SwitchToAnimation(new) => {
-                            animation.animation_tag = new.to_string();
-                            animation.current_frame = 0;
+                    if let AnimationOption::Emit(name) = action {
+                        events.send(AnimationEvent {
+                            entity,
+                            name: name.clone(),
+                        });
+                    }
+                }
+            }
+            let delay = current_frame.delay_ms as f32;
+            if animation.timer < delay {
+                break;
+            }
+            animation.timer -= delay;
+            let at_end = animation.at_end(frames);
+            let forward = animation.mode == PlaybackMode::Forward;
+            let mut next_frame = None;
+            let mut switch_to = None;
+            for action in current_frame.action.iter() {
+                match action {
+                    AnimationOption::None => {},
+                    AnimationOption::NextFrame if forward => {
+                        next_frame = Some(animation.current_frame + 1);
+                    }
+                    AnimationOption::GoToFrame(frame) if forward => {
+                        next_frame = Some(*frame);
+                    }
+                    AnimationOption::NextFrame | AnimationOption::GoToFrame(_) => {}
+                    AnimationOption::SwitchToAnimation(new) => {
+                        switch_to = Some(new.clone());
+                    }
+                    AnimationOption::PlaySound(tag) => {
+                        assets.play(tag, & mut commands, & loaded_assets);
+                    }
+                    // Sent when the frame was shown
+                    AnimationOption::Emit(_) => {}
+                }
+            }
+            match (animation.one_shot.clone(), at_end) {
+                (Some(one_shot), true) => match one_shot.end {
+                    OneShotEnd::Hold => animation.finished = true,
+                    OneShotEnd::Despawn => {
+                        commands.entity(entity).despawn_recursive();
+                        // A layer that despawns itself leaves its group
+                        if let Some(parent) = parent.map(Parent::get) {
+                            commands.add(move |world: &mut World| {
+                                if let Some(mut layers) = world.get_mut::<crate::AnimationLayers>(parent) {
+                                    layers.forget(entity);
+                                }
+                            });
                         }
-                        AnimationOption::PlaySound(tag) => {
-                            assets.play(tag, & mut commands, & loaded_assets);
+                        return;
+                    }
+                    OneShotEnd::Return => {
+                        animation.one_shot = None;
+                        if one_shot.previous != animation.animation_tag {
+                            animation.start(one_shot.previous);
                         }
                     }
-                    sprite.index = cycle
-                        .frames[animation.current_frame]
-                        .sprite_index;
-                }
+                },
+                _ => match (switch_to, next_frame) {
+                    (Some(new), _) => animation.start(new),
+                    // Out of range frames can only come from unvalidated
+                    // animations; stay put rather than panic
+                    (None, Some(frame)) if frame < frames => {
+                        animation.current_frame = frame;
+                        animation.emitted = false;
+                    }
+                    (None, Some(_)) => {}
+                    // Without a NextFrame or GoToFrame, a forward
+                    // animation holds its frame
+                    (None, None) if forward => {
+                        animation.timer = 0.0;
+                        break;
+                    }
+                    (None, None) => animation.step(frames),
+                },
             }
-        } else {
-            log::warn ! ("Animation Cycle [{}] not found!",
-            animation.animation_tag);
+        }
+        if let Some(cycle) = animations.0
+            .get(&animation.animation_tag)
+            .filter(|cycle| !cycle.frames.is_empty())
+        {
+            animation.begin(cycle.frames.len());
+            sprite.index = cycle.frames[animation.current_frame].sprite_index;
         }
     });
 }
+/// Scrolls a sprite a whole step every `move_every_ms`, jumping back by
+/// `image_width` once it has scrolled off the left. [`Parallax`](crate::Parallax)
+/// moves smoothly with the camera and tiles itself.
 #[derive(Component)]
 pub struct ContinualParallax {
     image_width: f32,
@@ -163,4 +517,206 @@ macro_rules! spawn_animated_sprite {
      .insert($component)
   )*
  };
-}
\ No newline at end of file
+}
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use bevy::time::TimeUpdateStrategy;
+    use std::time::Duration;
+
+    fn sequence(mode: PlaybackMode, frames: usize, steps: usize) -> Vec<usize> {
+        let mut cycle = AnimationCycle::new("test").with_mode(mode);
+        cycle.begin(frames);
+        (0..steps)
+            .map(|_| {
+                let frame = cycle.frame();
+                cycle.step(frames);
+                frame
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_playback_modes() {
+        assert_eq!(sequence(PlaybackMode::Reverse, 3, 5), vec![2, 1, 0, 2, 1]);
+        assert_eq!(sequence(PlaybackMode::PingPong, 3, 7), vec![0, 1, 2, 1, 0, 1, 2]);
+        assert_eq!(sequence(PlaybackMode::PingPong, 1, 3), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_one_shot_returns_to_first_animation() {
+        let mut cycle = AnimationCycle::new("idle");
+        cycle.play_once("attack", OneShotEnd::Return);
+        cycle.play_once("attack", OneShotEnd::Return);
+        cycle.play_once("hurt", OneShotEnd::Return);
+        assert_eq!(cycle.tag(), "hurt");
+        assert_eq!(cycle.one_shot.as_ref().unwrap().previous, "idle");
+        cycle.switch("run");
+        assert!(cycle.one_shot.is_none());
+    }
+
+    #[test]
+    fn test_speed_and_pause() {
+        let mut cycle = AnimationCycle::new("idle").with_speed(-1.0);
+        assert_eq!(cycle.speed(), 0.0);
+        cycle.pause();
+        assert!(cycle.is_paused());
+        cycle.resume();
+        assert!(!cycle.is_paused());
+    }
+
+    fn frame(sprite_index: usize, delay_ms: u128, action: Vec<AnimationOption>) -> AnimationFrame {
+        AnimationFrame::new(sprite_index, delay_ms, action)
+    }
+
+    #[test]
+    fn test_with_animation_validates() {
+        let walk = || PerFrameAnimation::new(vec![
+            frame(0, 100, vec![AnimationOption::NextFrame]),
+            frame(1, 100, vec![AnimationOption::GoToFrame(0)]),
+        ]);
+        assert!(Animations::new().with_animation("walk", walk()).is_ok());
+        let past_end = PerFrameAnimation::new(vec![frame(0, 100, vec![AnimationOption::NextFrame])]);
+        assert!(Animations::new().with_animation("a", past_end).is_err());
+        let bad_goto = PerFrameAnimation::new(vec![frame(0, 100, vec![AnimationOption::GoToFrame(1)])]);
+        assert!(Animations::new().with_animation("a", bad_goto).is_err());
+        let switch = || PerFrameAnimation::new(vec![
+            frame(0, 100, vec![AnimationOption::SwitchToAnimation("walk".to_string())]),
+        ]);
+        assert!(Animations::new().with_animation("jump", switch()).is_err());
+        assert!(Animations::new()
+            .with_animation("walk", walk())
+            .and_then(|a| a.with_animation("jump", switch()))
+            .is_ok());
+        assert!(Animations::new().with_animation("empty", PerFrameAnimation::new(Vec::new())).is_err());
+    }
+
+    pub(crate) fn headless(animations: Animations) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(animations)
+            .insert_resource(crate::AssetStore {
+                asset_index: HashMap::new(),
+                atlases_to_build: Vec::new(),
+                sheets_to_build: Vec::new(),
+                atlases: HashMap::new(),
+            })
+            .init_resource::<crate::LoadedAssets>()
+            .add_event::<AnimationEvent>()
+            .add_systems(Update, cycle_animations);
+        // The first update starts the clock
+        advance(&mut app, 0);
+        app
+    }
+
+    pub(crate) fn advance(app: &mut App, ms: u64) {
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(ms)));
+        app.update();
+    }
+
+    fn sprite(app: &App, entity: Entity) -> usize {
+        app.world.get::<TextureAtlasSprite>(entity).unwrap().index
+    }
+
+    fn walk_cycle() -> Animations {
+        Animations::new()
+            .with_animation("walk", PerFrameAnimation::new(vec![
+                frame(10, 100, vec![AnimationOption::NextFrame]),
+                frame(11, 100, vec![AnimationOption::NextFrame]),
+                frame(12, 100, vec![AnimationOption::GoToFrame(0)]),
+            ]))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_frame_accurate_playback() {
+        let mut app = headless(walk_cycle());
+        let walker = app.world
+            .spawn((AnimationCycle::new("walk"), TextureAtlasSprite::default()))
+            .id();
+        advance(&mut app, 0);
+        assert_eq!(sprite(&app, walker), 10);
+        // A slow update covers two whole frames...
+        advance(&mut app, 230);
+        assert_eq!(sprite(&app, walker), 12);
+        // ...and the 30ms left over counts towards the next
+        advance(&mut app, 80);
+        assert_eq!(sprite(&app, walker), 10);
+        advance(&mut app, 90);
+        assert_eq!(sprite(&app, walker), 11);
+    }
+
+    #[test]
+    fn test_speed_in_app() {
+        let mut app = headless(walk_cycle());
+        let walker = app.world
+            .spawn((AnimationCycle::new("walk").with_speed(2.0), TextureAtlasSprite::default()))
+            .id();
+        advance(&mut app, 100);
+        assert_eq!(sprite(&app, walker), 12);
+        app.world.get_mut::<AnimationCycle>(walker).unwrap().pause();
+        advance(&mut app, 200);
+        assert_eq!(sprite(&app, walker), 12);
+    }
+
+    #[test]
+    fn test_one_shot_in_app() {
+        let animations = walk_cycle()
+            .with_animation("explode", PerFrameAnimation::new(vec![
+                frame(20, 50, vec![AnimationOption::NextFrame]),
+                frame(21, 50, vec![AnimationOption::Emit("bang".to_string())]),
+            ]))
+            .unwrap();
+        let mut app = headless(animations);
+        let mut explosion = AnimationCycle::new("explode");
+        explosion.play_once("explode", OneShotEnd::Despawn);
+        let explosion = app.world.spawn((explosion, TextureAtlasSprite::default())).id();
+        let mut walker = AnimationCycle::new("walk");
+        walker.play_once("explode", OneShotEnd::Return);
+        let walker = app.world.spawn((walker, TextureAtlasSprite::default())).id();
+        let mut holder = AnimationCycle::new("walk").with_mode(PlaybackMode::Reverse);
+        holder.play_once("explode", OneShotEnd::Hold);
+        let holder = app.world.spawn((holder, TextureAtlasSprite::default())).id();
+
+        advance(&mut app, 120);
+        assert!(app.world.get_entity(explosion).is_none());
+        assert_eq!(app.world.get::<AnimationCycle>(walker).unwrap().tag(), "walk");
+        assert_eq!(sprite(&app, walker), 10);
+        assert!(app.world.get::<AnimationCycle>(holder).unwrap().is_finished());
+        assert_eq!(sprite(&app, holder), 20);
+
+        let events = app.world.resource::<Events<AnimationEvent>>();
+        let mut emitted: Vec<Entity> = events.get_reader().read(events).map(|e| e.entity).collect();
+        emitted.sort();
+        // The reversed one-shot plays the emitting frame first
+        let mut expected = vec![explosion, walker, holder];
+        expected.sort();
+        assert_eq!(emitted, expected);
+    }
+    #[test]
+    fn test_emit_when_frame_is_shown() {
+        let animations = Animations::new()
+            .with_animation("attack", PerFrameAnimation::new(vec![
+                frame(0, 100, vec![AnimationOption::NextFrame]),
+                frame(1, 100, vec![AnimationOption::Emit("hit".to_string()), AnimationOption::NextFrame]),
+                frame(2, 100, vec![AnimationOption::GoToFrame(0)]),
+            ]))
+            .unwrap();
+        let mut app = headless(animations);
+        let attacker = app.world.spawn((AnimationCycle::new("attack"), TextureAtlasSprite::default())).id();
+        let mut reader = app.world.resource::<Events<AnimationEvent>>().get_reader();
+        let mut sent = |app: &mut App, ms: u64| {
+            advance(app, ms);
+            let events = app.world.resource::<Events<AnimationEvent>>();
+            reader.read(events).cloned().collect::<Vec<_>>()
+        };
+        assert!(sent(&mut app, 0).is_empty());
+        // Sent as the frame appears, not when it ends
+        assert_eq!(sent(&mut app, 100), vec![AnimationEvent { entity: attacker, name: "hit".to_string() }]);
+        assert_eq!(sprite(&app, attacker), 1);
+        assert!(sent(&mut app, 50).is_empty());
+        assert!(sent(&mut app, 50).is_empty());
+        assert!(sent(&mut app, 100).is_empty());
+        assert_eq!(sent(&mut app, 100).len(), 1);
+    }
+}
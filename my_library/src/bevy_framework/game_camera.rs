@@ -0,0 +1,366 @@
+use bevy::{prelude::*, transform::TransformSystem};
+use rand::Rng;
+use crate::Noise;
+
+/// Adds [`GameCamera`] support: following, bounds, zoom and screen
+/// shake. Cameras are updated after `Update`, so they see where
+/// everything moved this frame.
+pub struct GameCameraPlugin;
+
+impl Plugin for GameCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            update_game_cameras.before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// `GameCamera` controls a 2D camera. It can:
+///
+/// * Follow a target entity. The target moves freely inside a
+///   dead-zone around the centre of the screen; once it leaves, the
+///   camera glides after it.
+/// * Stay inside the world's bounds, so it never shows past the edge
+///   of the map.
+/// * Zoom smoothly in and out.
+/// * Shake. Each hit adds *trauma*, which fades over time; the shake
+///   grows with the square of the trauma, so small knocks barely
+///   register while big crashes rattle the screen. The shake follows
+///   noise seeded from a
+///   [`RandomNumberGenerator`](crate::RandomNumberGenerator), so a
+///   replay with the same seed shakes the same way.
+///
+/// Add [`GameCameraPlugin`] to the app, and `GameCamera` next to a
+/// `Camera2dBundle`.
+///
+/// ## Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use my_library::*;
+///
+/// fn setup(mut commands: Commands, mut streams: ResMut<RandomStreams>) {
+///     let player = commands.spawn(SpriteBundle::default()).id();
+///     commands.spawn(Camera2dBundle::default()).insert(
+///         GameCamera::new(streams.stream("camera"))
+///             .following(player)
+///             .with_dead_zone(Vec2::new(200.0, 100.0))
+///             .with_bounds(Rect::new(-1024.0, -768.0, 1024.0, 768.0)),
+///     );
+/// }
+///
+/// fn explode(mut cameras: Query<&mut GameCamera>) {
+///     cameras.for_each_mut(|mut camera| camera.add_trauma(0.6));
+/// }
+/// ```
+#[derive(Component, Clone, Debug)]
+pub struct GameCamera {
+    target: Option<Entity>,
+    dead_zone: Vec2,
+    follow_speed: f32,
+    bounds: Option<Rect>,
+    zoom: f32,
+    zoom_target: f32,
+    zoom_speed: f32,
+    trauma: f32,
+    trauma_decay: f32,
+    max_shake: Vec2,
+    max_roll: f32,
+    shake_frequency: f32,
+    noise: Noise,
+    clock: f32,
+    focus: Option<Vec2>,
+}
+
+impl GameCamera {
+    /// Creates a camera controller that shakes according to noise
+    /// seeded from `rng`.
+    pub fn new<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self {
+            target: None,
+            dead_zone: Vec2::ZERO,
+            follow_speed: 5.0,
+            bounds: None,
+            zoom: 1.0,
+            zoom_target: 1.0,
+            zoom_speed: 5.0,
+            trauma: 0.0,
+            trauma_decay: 1.0,
+            max_shake: Vec2::new(30.0, 30.0),
+            max_roll: 0.05,
+            shake_frequency: 15.0,
+            noise: Noise::new(rng),
+            clock: 0.0,
+            focus: None,
+        }
+    }
+
+    /// Follows `target`, starting centred on it.
+    pub fn following(mut self, target: Entity) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    /// The target may move anywhere inside a `size` box around the
+    /// centre of the screen without moving the camera.
+    pub fn with_dead_zone(mut self, size: Vec2) -> Self {
+        self.dead_zone = size.max(Vec2::ZERO) / 2.0;
+        self
+    }
+
+    /// How quickly the camera catches up with its target. Higher is
+    /// snappier; `f32::INFINITY` keeps the target pinned to the edge
+    /// of the dead-zone.
+    pub fn with_follow_speed(mut self, speed: f32) -> Self {
+        self.follow_speed = speed.max(0.0);
+        self
+    }
+
+    /// Keeps the view inside `bounds`. If the world is smaller than the
+    /// view, the camera centres on it.
+    pub fn with_bounds(mut self, bounds: Rect) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    /// Starts at `zoom`: `2.0` shows everything twice as large.
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = zoom.max(0.01);
+        self.zoom_target = self.zoom;
+        self
+    }
+
+    /// Sets the largest shake, at full trauma: `offset` pixels on each
+    /// axis and `roll` radians.
+    pub fn with_shake(mut self, offset: Vec2, roll: f32) -> Self {
+        self.max_shake = offset;
+        self.max_roll = roll;
+        self
+    }
+
+    /// Sets how much trauma fades each second.
+    pub fn with_trauma_decay(mut self, per_second: f32) -> Self {
+        self.trauma_decay = per_second.max(0.0);
+        self
+    }
+
+    /// Follows a different entity, or stops following with `None`.
+    pub fn set_target(&mut self, target: Option<Entity>) {
+        self.target = target;
+    }
+
+    /// Changes (or removes) the bounds.
+    pub fn set_bounds(&mut self, bounds: Option<Rect>) {
+        self.bounds = bounds;
+    }
+
+    /// Zooms smoothly to `zoom`.
+    pub fn zoom_to(&mut self, zoom: f32) {
+        self.zoom_target = zoom.max(0.01);
+    }
+
+    /// The current zoom.
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Adds trauma, from `0.0` (calm) to `1.0` (the most shake).
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    /// The current trauma.
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Where the camera is looking, before shaking.
+    pub fn focus(&self) -> Option<Vec2> {
+        self.focus
+    }
+
+    /// Jumps straight to `position`, without gliding.
+    pub fn snap_to(&mut self, position: Vec2) {
+        self.focus = Some(position);
+    }
+
+    fn follow(&mut self, focus: Vec2, target: Vec2, seconds: f32) -> Vec2 {
+        let distance = target - focus;
+        let outside = distance - distance.clamp(-self.dead_zone, self.dead_zone);
+        if self.follow_speed.is_infinite() {
+            return focus + outside;
+        }
+        // Exponential smoothing, so the glide is the same at any frame rate
+        focus + outside * (1.0 - (-self.follow_speed * seconds).exp())
+    }
+
+    fn clamp(&self, focus: Vec2, half_view: Vec2) -> Vec2 {
+        let Some(bounds) = self.bounds else {
+            return focus;
+        };
+        let axis = |focus: f32, min: f32, max: f32, half: f32| {
+            if max - min <= half * 2.0 { (min + max) / 2.0 } else { focus.clamp(min + half, max - half) }
+        };
+        Vec2::new(
+            axis(focus.x, bounds.min.x, bounds.max.x, half_view.x),
+            axis(focus.y, bounds.min.y, bounds.max.y, half_view.y),
+        )
+    }
+
+    // Offset and roll for the current trauma
+    fn shake(&self) -> (Vec2, f32) {
+        let shake = self.trauma * self.trauma;
+        if shake <= 0.0 {
+            return (Vec2::ZERO, 0.0);
+        }
+        // Each channel reads its own row of noise, off the lattice
+        // (where Perlin noise is always zero)
+        let t = self.clock * self.shake_frequency;
+        let sample = |row: f32| self.noise.perlin_2d(t, row + 0.5);
+        (
+            self.max_shake * shake * Vec2::new(sample(1.0), sample(2.0)),
+            self.max_roll * shake * sample(3.0),
+        )
+    }
+}
+
+/// Moves, zooms and shakes every [`GameCamera`].
+/// [`GameCameraPlugin`] adds it for you.
+pub fn update_game_cameras(
+    mut cameras: Query<(&mut GameCamera, &mut Transform, &mut OrthographicProjection)>,
+    targets: Query<&Transform, Without<GameCamera>>,
+    time: Res<Time>,
+) {
+    let seconds = time.delta_seconds();
+    cameras.for_each_mut(|(mut camera, mut transform, mut projection)| {
+        let target = camera.target.and_then(|target| targets.get(target).ok())
+            .map(|target| target.translation.truncate());
+        let focus = match (camera.focus, target) {
+            (Some(focus), Some(target)) => camera.follow(focus, target, seconds),
+            (Some(focus), None) => focus,
+            (None, Some(target)) => target,
+            (None, None) => transform.translation.truncate(),
+        };
+
+        // The projection's area is the view at its current scale
+        let view = projection.area.size() / projection.scale;
+        let zoom_target = camera.zoom_target;
+        camera.zoom += (zoom_target - camera.zoom) * (1.0 - (-camera.zoom_speed * seconds).exp());
+        projection.scale = 1.0 / camera.zoom;
+
+        let focus = camera.clamp(focus, view * projection.scale / 2.0);
+        camera.focus = Some(focus);
+        camera.clock += seconds;
+        camera.trauma = (camera.trauma - camera.trauma_decay * seconds).max(0.0);
+        let (offset, roll) = camera.shake();
+        transform.translation.x = focus.x + offset.x;
+        transform.translation.y = focus.y + offset.y;
+        transform.rotation = Quat::from_rotation_z(roll);
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::RandomNumberGenerator;
+    use super::super::bevy_animation::test::advance;
+
+    fn headless(camera: GameCamera) -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_systems(Update, update_game_cameras);
+        let camera = app.world.spawn((
+            Transform::default(),
+            OrthographicProjection { area: Rect::new(-100.0, -50.0, 100.0, 50.0), ..default() },
+            camera,
+        )).id();
+        advance(&mut app, 0);
+        (app, camera)
+    }
+
+    fn position(app: &App, entity: Entity) -> Vec2 {
+        app.world.get::<Transform>(entity).unwrap().translation.truncate()
+    }
+
+    fn rng() -> RandomNumberGenerator {
+        RandomNumberGenerator::seeded(1)
+    }
+
+    #[test]
+    fn test_dead_zone_and_bounds() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins).add_systems(Update, update_game_cameras);
+        let player = app.world.spawn(Transform::default()).id();
+        let camera = app.world.spawn((
+            Transform::default(),
+            OrthographicProjection { area: Rect::new(-100.0, -50.0, 100.0, 50.0), ..default() },
+            GameCamera::new(&mut rng())
+                .following(player)
+                .with_dead_zone(Vec2::new(40.0, 20.0))
+                .with_follow_speed(f32::INFINITY)
+                .with_bounds(Rect::new(-150.0, -50.0, 150.0, 500.0)),
+        )).id();
+        advance(&mut app, 0);
+
+        app.world.get_mut::<Transform>(player).unwrap().translation = Vec3::new(15.0, 5.0, 0.0);
+        advance(&mut app, 16);
+        assert_eq!(position(&app, camera), Vec2::ZERO);
+
+        app.world.get_mut::<Transform>(player).unwrap().translation = Vec3::new(30.0, 30.0, 0.0);
+        advance(&mut app, 16);
+        assert_eq!(position(&app, camera), Vec2::new(10.0, 20.0));
+
+        // A pinned camera keeps up even on a frame where no time passes
+        app.world.get_mut::<Transform>(player).unwrap().translation = Vec3::new(40.0, 30.0, 0.0);
+        advance(&mut app, 0);
+        assert_eq!(position(&app, camera), Vec2::new(20.0, 20.0));
+
+        // The view is 200 wide, so it can't go past x = 50
+        app.world.get_mut::<Transform>(player).unwrap().translation = Vec3::new(500.0, -500.0, 0.0);
+        advance(&mut app, 16);
+        assert_eq!(position(&app, camera), Vec2::new(50.0, 0.0));
+    }
+
+    #[test]
+    fn test_smooth_follow_and_zoom() {
+        let (mut app, camera) = headless(GameCamera::new(&mut rng()).with_zoom(2.0));
+        assert_eq!(app.world.get::<OrthographicProjection>(camera).unwrap().scale, 0.5);
+        app.world.get_mut::<GameCamera>(camera).unwrap().zoom_to(1.0);
+        advance(&mut app, 100);
+        let zoom = app.world.get::<GameCamera>(camera).unwrap().zoom();
+        assert!(zoom > 1.0 && zoom < 2.0);
+        for _ in 0..50 {
+            advance(&mut app, 100);
+        }
+        assert!((app.world.get::<OrthographicProjection>(camera).unwrap().scale - 1.0).abs() < 1e-3);
+
+        let target = app.world.spawn(Transform::from_xyz(100.0, 0.0, 0.0)).id();
+        app.world.get_mut::<GameCamera>(camera).unwrap().set_target(Some(target));
+        advance(&mut app, 100);
+        let x = position(&app, camera).x;
+        assert!(x > 0.0 && x < 100.0);
+    }
+
+    #[test]
+    fn test_trauma_shakes_and_fades() {
+        let shaken = |seed: u64| {
+            let camera = GameCamera::new(&mut RandomNumberGenerator::seeded(seed));
+            let (mut app, camera) = headless(camera);
+            app.world.get_mut::<GameCamera>(camera).unwrap().add_trauma(2.0);
+            assert_eq!(app.world.get::<GameCamera>(camera).unwrap().trauma(), 1.0);
+            advance(&mut app, 100);
+            let offset = position(&app, camera);
+            for _ in 0..10 {
+                advance(&mut app, 100);
+            }
+            assert_eq!(position(&app, camera), Vec2::ZERO);
+            assert_eq!(app.world.get::<Transform>(camera).unwrap().rotation, Quat::IDENTITY);
+            offset
+        };
+        let offset = shaken(1);
+        assert!(offset.length() > 0.0);
+        assert_eq!(offset, shaken(1));
+        assert_ne!(offset, shaken(2));
+    }
+}
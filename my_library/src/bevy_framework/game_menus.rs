@@ -1,6 +1,6 @@
 use super::MenuResource;
 use bevy::{app::AppExit, prelude::*};
-use crate::{AssetStore};
+use crate::{AssetStore, Ease, Tween};
 
 #[derive(Component)]
 pub(crate) struct MenuElement;
@@ -15,11 +15,12 @@ pub(crate) fn setup<T>(// (1)
     T: States,
 {
     let current_state = state.get();// (2)
-    let menu_graphic = match current_state {// (3)
+    let (menu_graphic, delay) = match current_state {// (3)
         current_state if menu_resource.menu_state == *current_state =>
-            assets.get_handle("main_menu", &loaded_assets).unwrap(),
+            (assets.get_handle("main_menu", &loaded_assets).unwrap(), 0.0),
+        // Let the player see how they crashed before the menu arrives
         current_state if menu_resource.game_end_state == *current_state =>
-            assets.get_handle("game_over", &loaded_assets).unwrap(),
+            (assets.get_handle("game_over", &loaded_assets).unwrap(), 0.4),
         _ => panic!("Unknown menu state"),// (4)
     };
 
@@ -32,7 +33,13 @@ pub(crate) fn setup<T>(// (1)
             transform: Transform::from_xyz(0.0, 0.0, 1.0),
             ..default()
         })
-        .insert(MenuElement);
+        .insert(MenuElement)
+        .insert(
+            // Slide down into place while fading in
+            Tween::translation(Vec3::new(0.0, 120.0, 1.0), Vec3::new(0.0, 0.0, 1.0), 0.6, Ease::BackOut)
+                .with(Tween::sprite_alpha(0.0, 1.0, 0.4, Ease::QuadraticOut))
+                .with_delay(delay),
+        );
 }
 pub(crate) fn run<T>(
     keyboard: Res<Input<KeyCode>>,
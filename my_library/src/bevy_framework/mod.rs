@@ -4,9 +4,24 @@ mod game_menus;
 mod bevy_animation;
 pub use bevy_animation::*;
 
+mod animation_state;
+pub use animation_state::*;
+
+mod animation_layers;
+pub use animation_layers::*;
+
 mod bevy_physics;
 pub use bevy_physics::*;
 
+mod tween;
+pub use tween::*;
+
+mod parallax;
+pub use parallax::*;
+
+mod game_camera;
+pub use game_camera::*;
+
 pub struct GameStatePlugin<T> {
     menu_state: T,
     game_start_state: T,
@@ -56,6 +71,9 @@ where
         app.add_systems(OnExit(T::default()), crate::bevy_assets::exit);
         app.add_event::<PhysicsTick>();
         app.add_event::<Impulse>();
+        app.add_event::<AnimationEvent>();
+        app.add_event::<TweenCompleted>();
+        app.add_systems(Update, update_tweens);
     }
 }
 
@@ -70,7 +88,8 @@ pub fn cleanup<T>(query: Query<Entity, With<T>>, mut commands: Commands,
 ) where 
     T: Component 
 { 
-    query.for_each(|entity| commands.entity(entity).despawn()) 
+    // Recursive, so children such as animation layers go too
+    query.for_each(|entity| commands.entity(entity).despawn_recursive()) 
 }
 
 #[macro_export]
@@ -0,0 +1,249 @@
+use bevy::prelude::*;
+
+/// `Parallax` turns one image into a scrolling background layer that
+/// follows the camera. Each layer has a depth `factor`: how far it
+/// moves on screen as the camera moves through the world. `1.0` moves
+/// with the world, like the level itself; `0.0` stays fixed, like a
+/// distant sky. Anything in between gives the illusion of depth.
+///
+/// Layers can also scroll on their own (clouds drifting, or a
+/// side-scroller whose camera never moves), and tile their image on
+/// either axis. The layer spawns as many copies of the image as it
+/// needs to fill the view (as children, so
+/// [`cleanup`](crate::cleanup) removes them too) and respawns them
+/// when the view grows, such as when the camera zooms out.
+///
+/// Layers follow the first 2D camera, and are moved every frame by
+/// [`camera_parallax`] using sub-pixel positions, so motion stays
+/// smooth at any frame rate.
+///
+/// ## Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use my_library::*;
+///
+/// fn setup(mut commands: Commands, assets: Res<AssetStore>, loaded_assets: AssetResource) {
+///     // The layer's z sets its drawing order
+///     commands.spawn(SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, -10.0)))
+///         .insert(Parallax::new(assets.get_handle("mountains", &loaded_assets).unwrap(), 0.25)
+///             .with_tiling(BVec2::new(true, false))
+///             .with_scroll(Vec2::new(-10.0, 0.0)));
+/// }
+/// ```
+#[derive(Component, Clone, Debug)]
+pub struct Parallax {
+    image: Handle<Image>,
+    factor: Vec2,
+    tiling: BVec2,
+    scroll_speed: Vec2,
+    origin: Vec2,
+    scrolled: Vec2,
+    tiles: UVec2,
+    tile_size: Vec2,
+}
+
+impl Parallax {
+    /// A layer showing `image`, moving by `factor` as the camera
+    /// moves. It tiles on both axes.
+    pub fn new(image: Handle<Image>, factor: f32) -> Self {
+        Self {
+            image,
+            factor: Vec2::splat(factor),
+            tiling: BVec2::TRUE,
+            scroll_speed: Vec2::ZERO,
+            origin: Vec2::ZERO,
+            scrolled: Vec2::ZERO,
+            tiles: UVec2::ZERO,
+            tile_size: Vec2::ZERO,
+        }
+    }
+
+    /// Uses a different depth factor on each axis.
+    pub fn with_factor(mut self, factor: Vec2) -> Self {
+        self.factor = factor;
+        self
+    }
+
+    /// Chooses which axes repeat the image. An axis that doesn't tile
+    /// shows a single copy.
+    pub fn with_tiling(mut self, tiling: BVec2) -> Self {
+        self.tiling = tiling;
+        self
+    }
+
+    /// Scrolls the layer across the screen by `speed` pixels per
+    /// second, whether or not the camera moves.
+    pub fn with_scroll(mut self, speed: Vec2) -> Self {
+        self.scroll_speed = speed;
+        self
+    }
+
+    /// Where the image sits on screen when the camera is at the
+    /// origin.
+    pub fn with_origin(mut self, origin: Vec2) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Changes the layer's scroll speed.
+    pub fn set_scroll(&mut self, speed: Vec2) {
+        self.scroll_speed = speed;
+    }
+
+    /// The layer's scroll speed, in pixels per second.
+    pub fn scroll(&self) -> Vec2 {
+        self.scroll_speed
+    }
+
+    // How many copies cover `view` on each axis, with one spare on
+    // each side so wrapping never shows a gap
+    fn tiles_for(&self, view: Vec2, size: Vec2) -> UVec2 {
+        let count = |tiled: bool, view: f32, size: f32| {
+            if tiled { ((view / size + 1.0) / 2.0).ceil() as u32 * 2 + 1 } else { 1 }
+        };
+        UVec2::new(
+            count(self.tiling.x, view.x, size.x),
+            count(self.tiling.y, view.y, size.y),
+        )
+    }
+
+    // The layer's offset from the camera
+    fn screen_position(&self, camera: Vec2, size: Vec2) -> Vec2 {
+        let position = self.origin - camera * self.factor + self.scrolled;
+        let wrap = |tiled: bool, position: f32, size: f32| {
+            if tiled { position.rem_euclid(size) } else { position }
+        };
+        Vec2::new(
+            wrap(self.tiling.x, position.x, size.x),
+            wrap(self.tiling.y, position.y, size.y),
+        )
+    }
+}
+
+/// Moves every [`Parallax`] layer to match the camera, spawning its
+/// image copies once the image has loaded.
+#[allow(clippy::type_complexity)]
+pub fn camera_parallax(
+    mut layers: Query<(Entity, &mut Parallax, &mut Transform)>,
+    cameras: Query<(&Transform, &OrthographicProjection), (With<Camera2d>, Without<Parallax>)>,
+    images: Res<Assets<Image>>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    let Some((camera, projection)) = cameras.iter().next() else {
+        return;
+    };
+    let camera_position = camera.translation.truncate();
+    let view = projection.area.size();
+    layers.for_each_mut(|(entity, mut parallax, mut transform)| {
+        let Some(size) = images.get(&parallax.image).map(Image::size_f32) else {
+            return;
+        };
+        if size.x <= 0.0 || size.y <= 0.0 {
+            return;
+        }
+        let tiles = parallax.tiles_for(view, size);
+        if tiles != parallax.tiles || size != parallax.tile_size {
+            parallax.tiles = tiles;
+            parallax.tile_size = size;
+            let image = parallax.image.clone();
+            let first = -(tiles.as_ivec2() / 2);
+            commands.entity(entity).despawn_descendants().with_children(|layer| {
+                for x in 0..tiles.x as i32 {
+                    for y in 0..tiles.y as i32 {
+                        let offset = (first + IVec2::new(x, y)).as_vec2() * size;
+                        layer.spawn(SpriteBundle {
+                            texture: image.clone(),
+                            transform: Transform::from_xyz(offset.x, offset.y, 0.0),
+                            ..default()
+                        });
+                    }
+                }
+            });
+        }
+        let speed = parallax.scroll_speed;
+        parallax.scrolled += speed * time.delta_seconds();
+        // Keep the scrolled distance small, so it never loses precision
+        if parallax.tiling.x {
+            parallax.scrolled.x = parallax.scrolled.x.rem_euclid(size.x);
+        }
+        if parallax.tiling.y {
+            parallax.scrolled.y = parallax.scrolled.y.rem_euclid(size.y);
+        }
+        let position = camera_position + parallax.screen_position(camera_position, size);
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::bevy_animation::test::advance;
+    use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+    fn headless() -> (App, Handle<Image>, Entity) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<Assets<Image>>()
+            .add_systems(Update, camera_parallax);
+        let image = Image::new_fill(
+            Extent3d { width: 100, height: 50, depth_or_array_layers: 1 },
+            TextureDimension::D2,
+            &[0, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+        );
+        let image = app.world.resource_mut::<Assets<Image>>().add(image);
+        let camera = app.world.spawn((
+            Transform::default(),
+            OrthographicProjection { area: Rect::new(-200.0, -100.0, 200.0, 100.0), ..default() },
+            Camera2d::default(),
+        )).id();
+        advance(&mut app, 0);
+        (app, image, camera)
+    }
+
+    fn position(app: &App, entity: Entity) -> Vec2 {
+        app.world.get::<Transform>(entity).unwrap().translation.truncate()
+    }
+
+    #[test]
+    fn test_tiles_fill_the_view() {
+        let (mut app, image, _) = headless();
+        let layer = app.world.spawn((SpatialBundle::default(), Parallax::new(image.clone(), 0.5))).id();
+        let strip = app.world
+            .spawn((SpatialBundle::default(), Parallax::new(image, 0.5).with_tiling(BVec2::new(true, false))))
+            .id();
+        advance(&mut app, 0);
+        assert_eq!(app.world.get::<Children>(layer).unwrap().len(), 7 * 7);
+        assert_eq!(app.world.get::<Children>(strip).unwrap().len(), 7);
+    }
+
+    #[test]
+    fn test_follows_camera_by_depth() {
+        let (mut app, image, camera) = headless();
+        let layer = app.world.spawn((SpatialBundle::default(), Parallax::new(image, 0.5))).id();
+        advance(&mut app, 0);
+        assert_eq!(position(&app, layer), Vec2::ZERO);
+
+        app.world.get_mut::<Transform>(camera).unwrap().translation = Vec3::new(130.0, 20.0, 0.0);
+        advance(&mut app, 0);
+        // Half the camera's motion, wrapped to one tile
+        assert_eq!(position(&app, layer), Vec2::new(130.0 + 35.0, 20.0 + 40.0));
+    }
+
+    #[test]
+    fn test_scrolls_smoothly() {
+        let (mut app, image, _) = headless();
+        let layer = app.world.spawn((
+            SpatialBundle::default(),
+            Parallax::new(image, 0.0).with_tiling(BVec2::new(true, false)).with_scroll(Vec2::new(-40.0, 10.0)),
+        )).id();
+        advance(&mut app, 0);
+        advance(&mut app, 250);
+        assert!((position(&app, layer) - Vec2::new(90.0, 2.5)).length() < 1e-4);
+        advance(&mut app, 125);
+        assert!((position(&app, layer) - Vec2::new(85.0, 3.75)).length() < 1e-4);
+    }
+}
@@ -0,0 +1,669 @@
+use bevy::prelude::*;
+use std::f32::consts::{PI, TAU};
+use crate::PlaybackMode;
+
+/// The easing curves a [`Tween`] can follow. Each maps progress from
+/// 0 to 1 onto an eased value that starts at 0 and ends at 1 (`Back`
+/// and `Elastic` overshoot on the way).
+///
+/// `In` curves start slowly, `Out` curves end slowly and `InOut`
+/// curves do both.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Ease {
+    #[default]
+    Linear,
+    QuadraticIn,
+    QuadraticOut,
+    QuadraticInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    QuarticIn,
+    QuarticOut,
+    QuarticInOut,
+    QuinticIn,
+    QuinticOut,
+    QuinticInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    ExponentialIn,
+    ExponentialOut,
+    ExponentialInOut,
+    CircularIn,
+    CircularOut,
+    CircularInOut,
+    BackIn,
+    BackOut,
+    BackInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+}
+
+impl Ease {
+    /// Eases `t`, which is clamped to `0..=1`.
+    pub fn sample(self, t: f32) -> f32 {
+        use Ease::*;
+        let t = t.clamp(0.0, 1.0);
+        let (curve, shape): (fn(f32) -> f32, Shape) = match self {
+            Linear => return t,
+            QuadraticIn => (|t| t * t, Shape::In),
+            QuadraticOut => (|t| t * t, Shape::Out),
+            QuadraticInOut => (|t| t * t, Shape::InOut),
+            CubicIn => (|t| t.powi(3), Shape::In),
+            CubicOut => (|t| t.powi(3), Shape::Out),
+            CubicInOut => (|t| t.powi(3), Shape::InOut),
+            QuarticIn => (|t| t.powi(4), Shape::In),
+            QuarticOut => (|t| t.powi(4), Shape::Out),
+            QuarticInOut => (|t| t.powi(4), Shape::InOut),
+            QuinticIn => (|t| t.powi(5), Shape::In),
+            QuinticOut => (|t| t.powi(5), Shape::Out),
+            QuinticInOut => (|t| t.powi(5), Shape::InOut),
+            SineIn => (sine_in, Shape::In),
+            SineOut => (sine_in, Shape::Out),
+            SineInOut => (sine_in, Shape::InOut),
+            ExponentialIn => (exponential_in, Shape::In),
+            ExponentialOut => (exponential_in, Shape::Out),
+            ExponentialInOut => (exponential_in, Shape::InOut),
+            CircularIn => (circular_in, Shape::In),
+            CircularOut => (circular_in, Shape::Out),
+            CircularInOut => (circular_in, Shape::InOut),
+            BackIn => (back_in, Shape::In),
+            BackOut => (back_in, Shape::Out),
+            BackInOut => (back_in, Shape::InOut),
+            ElasticIn => (elastic_in, Shape::In),
+            ElasticOut => (elastic_in, Shape::Out),
+            ElasticInOut => (elastic_in, Shape::InOut),
+            BounceIn => (bounce_in, Shape::In),
+            BounceOut => (bounce_in, Shape::Out),
+            BounceInOut => (bounce_in, Shape::InOut),
+        };
+        // Every family is defined by its `In` curve: `Out` plays it
+        // backwards and upside down, `InOut` plays half of each
+        match shape {
+            Shape::In => curve(t),
+            Shape::Out => 1.0 - curve(1.0 - t),
+            Shape::InOut if t < 0.5 => curve(2.0 * t) / 2.0,
+            Shape::InOut => 1.0 - curve(2.0 - 2.0 * t) / 2.0,
+        }
+    }
+}
+
+enum Shape {
+    In,
+    Out,
+    InOut,
+}
+
+fn sine_in(t: f32) -> f32 {
+    1.0 - (t * PI / 2.0).cos()
+}
+
+fn exponential_in(t: f32) -> f32 {
+    if t <= 0.0 { 0.0 } else { 2.0f32.powf(10.0 * t - 10.0) }
+}
+
+fn circular_in(t: f32) -> f32 {
+    1.0 - (1.0 - t * t).sqrt()
+}
+
+fn back_in(t: f32) -> f32 {
+    const OVERSHOOT: f32 = 1.70158;
+    (OVERSHOOT + 1.0) * t.powi(3) - OVERSHOOT * t * t
+}
+
+fn elastic_in(t: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        return t;
+    }
+    -(2.0f32.powf(10.0 * t - 10.0)) * ((10.0 * t - 10.75) * TAU / 3.0).sin()
+}
+
+fn bounce_in(t: f32) -> f32 {
+    1.0 - bounce_out(1.0 - t)
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+/// What a [`Tween`] changes, and the values it moves between.
+#[derive(Clone, Debug, PartialEq)]
+#[allow(missing_docs)]
+pub enum TweenTarget {
+    /// The `Transform`'s translation.
+    Translation { from: Vec3, to: Vec3 },
+    /// The `Transform`'s rotation around the Z axis, in radians.
+    Rotation { from: f32, to: f32 },
+    /// The `Transform`'s scale.
+    Scale { from: Vec3, to: Vec3 },
+    /// The tint of a `Sprite` or `TextureAtlasSprite`.
+    SpriteColor { from: Color, to: Color },
+    /// The alpha of a `Sprite` or `TextureAtlasSprite`, keeping its tint.
+    SpriteAlpha { from: f32, to: f32 },
+    /// The color of every section of a `Text`.
+    TextColor { from: Color, to: Color },
+    /// The alpha of every section of a `Text`, keeping its color.
+    TextAlpha { from: f32, to: f32 },
+    /// A UI node's `BackgroundColor`.
+    UiColor { from: Color, to: Color },
+}
+
+impl TweenTarget {
+    // Tracks with the same channel fight over the same value
+    fn channel(&self) -> u8 {
+        match self {
+            Self::Translation { .. } => 1,
+            Self::Rotation { .. } => 2,
+            Self::Scale { .. } => 4,
+            Self::SpriteColor { .. } | Self::SpriteAlpha { .. } => 8,
+            Self::TextColor { .. } | Self::TextAlpha { .. } => 16,
+            Self::UiColor { .. } => 32,
+        }
+    }
+
+    fn apply(&self, eased: f32, targets: &mut Targets) {
+        match self {
+            Self::Translation { from, to } => {
+                if let Some(transform) = targets.transform.as_mut() {
+                    transform.translation = from.lerp(*to, eased);
+                }
+            }
+            Self::Rotation { from, to } => {
+                if let Some(transform) = targets.transform.as_mut() {
+                    transform.rotation = Quat::from_rotation_z(from + (to - from) * eased);
+                }
+            }
+            Self::Scale { from, to } => {
+                if let Some(transform) = targets.transform.as_mut() {
+                    transform.scale = from.lerp(*to, eased);
+                }
+            }
+            Self::SpriteColor { from, to } => {
+                targets.sprite_color(|color| *color = lerp_color(*from, *to, eased));
+            }
+            Self::SpriteAlpha { from, to } => {
+                targets.sprite_color(|color| {
+                    color.set_a(lerp_alpha(*from, *to, eased));
+                });
+            }
+            Self::TextColor { from, to } => {
+                if let Some(text) = targets.text.as_mut() {
+                    let color = lerp_color(*from, *to, eased);
+                    text.sections.iter_mut().for_each(|section| section.style.color = color);
+                }
+            }
+            Self::TextAlpha { from, to } => {
+                if let Some(text) = targets.text.as_mut() {
+                    let alpha = lerp_alpha(*from, *to, eased);
+                    text.sections.iter_mut().for_each(|section| {
+                        section.style.color.set_a(alpha);
+                    });
+                }
+            }
+            Self::UiColor { from, to } => {
+                if let Some(background) = targets.background.as_mut() {
+                    background.0 = lerp_color(*from, *to, eased);
+                }
+            }
+        }
+    }
+}
+
+fn lerp_color(from: Color, to: Color, t: f32) -> Color {
+    let [r, g, b, a] = from.as_rgba_f32();
+    let [r2, g2, b2, a2] = to.as_rgba_f32();
+    Color::rgba(
+        r + (r2 - r) * t,
+        g + (g2 - g) * t,
+        b + (b2 - b) * t,
+        (a + (a2 - a) * t).clamp(0.0, 1.0),
+    )
+}
+
+fn lerp_alpha(from: f32, to: f32, t: f32) -> f32 {
+    (from + (to - from) * t).clamp(0.0, 1.0)
+}
+
+struct Targets<'a> {
+    transform: Option<&'a mut Transform>,
+    sprite: Option<&'a mut Sprite>,
+    atlas_sprite: Option<&'a mut TextureAtlasSprite>,
+    text: Option<&'a mut Text>,
+    background: Option<&'a mut BackgroundColor>,
+}
+
+impl Targets<'_> {
+    fn sprite_color(&mut self, mut change: impl FnMut(&mut Color)) {
+        if let Some(sprite) = self.sprite.as_mut() {
+            change(&mut sprite.color);
+        }
+        if let Some(sprite) = self.atlas_sprite.as_mut() {
+            change(&mut sprite.color);
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Track {
+    start: f32,
+    duration: f32,
+    target: TweenTarget,
+    ease: Ease,
+}
+
+/// How many times a [`Tween`] plays.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TweenRepeat {
+    /// Play once.
+    #[default]
+    Once,
+    /// Play this many times in total.
+    Times(u32),
+    /// Play until the `Tween` is removed.
+    Forever,
+}
+
+/// Sent by [`update_tweens`] when a [`Tween`] finishes its last play.
+/// Read it with `EventReader<TweenCompleted>`.
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct TweenCompleted {
+    /// The tweened entity.
+    pub entity: Entity,
+    /// The name given to [`Tween::on_complete`], if any.
+    pub name: Option<String>,
+}
+
+/// `Tween` smoothly moves an entity's `Transform`, sprite color or
+/// text (or a UI node's `BackgroundColor`) from one value to another,
+/// following an [`Ease`] curve.
+///
+/// Tweens combine: [`then`](Self::then) plays one after another and
+/// [`with`](Self::with) plays them side by side, so a single `Tween`
+/// can slide a sprite in while it fades up, then pulse its scale.
+/// The whole tween can then be delayed, repeated, reversed or played
+/// back and forth with a [`PlaybackMode`].
+///
+/// [`GameStatePlugin`](crate::GameStatePlugin) runs tweens in every
+/// state, and sends [`TweenCompleted`] as each one finishes.
+///
+/// ## Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use my_library::*;
+///
+/// fn setup(mut commands: Commands) {
+///     commands.spawn(SpriteBundle::default()).insert(
+///         Tween::translation(Vec3::new(0.0, 300.0, 1.0), Vec3::new(0.0, 0.0, 1.0), 0.6, Ease::BackOut)
+///             .with(Tween::sprite_alpha(0.0, 1.0, 0.4, Ease::QuadraticOut))
+///             .then(Tween::scale(Vec3::ONE, Vec3::splat(1.1), 0.3, Ease::SineInOut))
+///             .with_delay(0.5)
+///             .on_complete("title_shown"),
+///     );
+/// }
+/// ```
+#[derive(Component, Clone, Debug)]
+pub struct Tween {
+    tracks: Vec<Track>,
+    duration: f32,
+    elapsed: f32,
+    mode: PlaybackMode,
+    repeat: TweenRepeat,
+    plays: u32,
+    name: Option<String>,
+    finished: bool,
+}
+
+impl Tween {
+    /// Moves `target` over `seconds`, following `ease`.
+    pub fn new(target: TweenTarget, seconds: f32, ease: Ease) -> Self {
+        let duration = seconds.max(0.0);
+        Self {
+            tracks: vec![Track { start: 0.0, duration, target, ease }],
+            ..Self::delay(duration)
+        }
+    }
+
+    /// A tween that changes nothing for `seconds`, as a pause inside a
+    /// [`then`](Self::then) chain.
+    pub fn delay(seconds: f32) -> Self {
+        Self {
+            tracks: Vec::new(),
+            duration: seconds.max(0.0),
+            elapsed: 0.0,
+            mode: PlaybackMode::Forward,
+            repeat: TweenRepeat::Once,
+            plays: 0,
+            name: None,
+            finished: false,
+        }
+    }
+
+    /// Moves the translation from `from` to `to`.
+    pub fn translation(from: Vec3, to: Vec3, seconds: f32, ease: Ease) -> Self {
+        Self::new(TweenTarget::Translation { from, to }, seconds, ease)
+    }
+
+    /// Turns around the Z axis from `from` to `to` radians.
+    pub fn rotation(from: f32, to: f32, seconds: f32, ease: Ease) -> Self {
+        Self::new(TweenTarget::Rotation { from, to }, seconds, ease)
+    }
+
+    /// Scales from `from` to `to`.
+    pub fn scale(from: Vec3, to: Vec3, seconds: f32, ease: Ease) -> Self {
+        Self::new(TweenTarget::Scale { from, to }, seconds, ease)
+    }
+
+    /// Tints a sprite from `from` to `to`.
+    pub fn sprite_color(from: Color, to: Color, seconds: f32, ease: Ease) -> Self {
+        Self::new(TweenTarget::SpriteColor { from, to }, seconds, ease)
+    }
+
+    /// Fades a sprite from `from` to `to` alpha.
+    pub fn sprite_alpha(from: f32, to: f32, seconds: f32, ease: Ease) -> Self {
+        Self::new(TweenTarget::SpriteAlpha { from, to }, seconds, ease)
+    }
+
+    /// Changes a text's color from `from` to `to`.
+    pub fn text_color(from: Color, to: Color, seconds: f32, ease: Ease) -> Self {
+        Self::new(TweenTarget::TextColor { from, to }, seconds, ease)
+    }
+
+    /// Fades a text from `from` to `to` alpha.
+    pub fn text_alpha(from: f32, to: f32, seconds: f32, ease: Ease) -> Self {
+        Self::new(TweenTarget::TextAlpha { from, to }, seconds, ease)
+    }
+
+    /// Changes a UI node's background from `from` to `to`.
+    pub fn ui_color(from: Color, to: Color, seconds: f32, ease: Ease) -> Self {
+        Self::new(TweenTarget::UiColor { from, to }, seconds, ease)
+    }
+
+    /// Plays `tweens` one after another.
+    pub fn sequence(tweens: impl IntoIterator<Item = Tween>) -> Self {
+        tweens.into_iter().fold(Self::delay(0.0), Self::then)
+    }
+
+    /// Plays `tweens` side by side.
+    pub fn parallel(tweens: impl IntoIterator<Item = Tween>) -> Self {
+        tweens.into_iter().fold(Self::delay(0.0), Self::with)
+    }
+
+    /// Plays `next` once this tween ends. `next`'s own delay, mode,
+    /// repeat and completion name are ignored.
+    pub fn then(mut self, next: Tween) -> Self {
+        let offset = self.duration;
+        self.tracks.extend(next.tracks.into_iter().map(|track| Track {
+            start: track.start + offset,
+            ..track
+        }));
+        self.duration += next.duration;
+        self
+    }
+
+    /// Plays `other` alongside this tween. `other`'s own delay, mode,
+    /// repeat and completion name are ignored.
+    pub fn with(mut self, other: Tween) -> Self {
+        self.tracks.extend(other.tracks);
+        // Stable, so later tweens still win ties over the same value
+        self.tracks.sort_by(|a, b| a.start.total_cmp(&b.start));
+        self.duration = self.duration.max(other.duration);
+        self
+    }
+
+    /// Waits `seconds` before starting. The wait happens once, not on
+    /// every repeat.
+    pub fn with_delay(mut self, seconds: f32) -> Self {
+        self.elapsed = -seconds.max(0.0);
+        self
+    }
+
+    /// Plays forwards, backwards, or back and forth on each repeat.
+    pub fn with_mode(mut self, mode: PlaybackMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets how many times the tween plays.
+    pub fn with_repeat(mut self, repeat: TweenRepeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Names the [`TweenCompleted`] event sent when the tween finishes.
+    pub fn on_complete<S: ToString>(mut self, name: S) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// How long one play lasts, in seconds.
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// Has the tween finished its last play?
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn reversed(&self) -> bool {
+        match self.mode {
+            PlaybackMode::Forward => false,
+            PlaybackMode::Reverse => true,
+            PlaybackMode::PingPong => self.plays % 2 == 1,
+        }
+    }
+
+    fn advance(&mut self, seconds: f32) {
+        self.elapsed += seconds;
+        while self.elapsed >= self.duration {
+            let more = self.duration > 0.0
+                && match self.repeat {
+                    TweenRepeat::Once => false,
+                    TweenRepeat::Times(times) => self.plays + 1 < times,
+                    TweenRepeat::Forever => true,
+                };
+            if !more {
+                self.elapsed = self.duration;
+                self.finished = true;
+                break;
+            }
+            self.elapsed -= self.duration;
+            self.plays += 1;
+        }
+    }
+
+    fn apply(&self, targets: &mut Targets) {
+        let elapsed = self.elapsed.max(0.0);
+        let time = if self.reversed() { self.duration - elapsed } else { elapsed };
+        let mut started = 0;
+        for track in self.tracks.iter() {
+            // A value's first track holds its starting value until it
+            // begins; after that, the latest track to start wins
+            let channel = track.target.channel();
+            if time < track.start && started & channel != 0 {
+                continue;
+            }
+            started |= channel;
+            let progress = if track.duration > 0.0 {
+                (time - track.start) / track.duration
+            } else if time >= track.start {
+                1.0
+            } else {
+                0.0
+            };
+            track.target.apply(track.ease.sample(progress), targets);
+        }
+    }
+}
+
+/// Plays every [`Tween`]. [`GameStatePlugin`](crate::GameStatePlugin)
+/// adds it for you.
+#[allow(clippy::type_complexity)]
+pub fn update_tweens(
+    mut tweens: Query<(
+        Entity,
+        &mut Tween,
+        Option<&mut Transform>,
+        Option<&mut Sprite>,
+        Option<&mut TextureAtlasSprite>,
+        Option<&mut Text>,
+        Option<&mut BackgroundColor>,
+    )>,
+    time: Res<Time>,
+    mut events: EventWriter<TweenCompleted>,
+) {
+    tweens.for_each_mut(|(entity, mut tween, mut transform, mut sprite, mut atlas_sprite, mut text, mut background)| {
+        if tween.finished {
+            return;
+        }
+        tween.advance(time.delta_seconds());
+        tween.apply(&mut Targets {
+            transform: transform.as_deref_mut(),
+            sprite: sprite.as_deref_mut(),
+            atlas_sprite: atlas_sprite.as_deref_mut(),
+            text: text.as_deref_mut(),
+            background: background.as_deref_mut(),
+        });
+        if tween.finished {
+            events.send(TweenCompleted { entity, name: tween.name.clone() });
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::bevy_animation::test::advance;
+
+    #[test]
+    fn test_ease_endpoints() {
+        use Ease::*;
+        let all = [
+            Linear, QuadraticIn, QuadraticOut, QuadraticInOut, CubicIn, CubicOut, CubicInOut,
+            QuarticIn, QuarticOut, QuarticInOut, QuinticIn, QuinticOut, QuinticInOut,
+            SineIn, SineOut, SineInOut, ExponentialIn, ExponentialOut, ExponentialInOut,
+            CircularIn, CircularOut, CircularInOut, BackIn, BackOut, BackInOut,
+            ElasticIn, ElasticOut, ElasticInOut, BounceIn, BounceOut, BounceInOut,
+        ];
+        for ease in all {
+            assert!(ease.sample(0.0).abs() < 1e-3, "{ease:?}");
+            assert!((ease.sample(1.0) - 1.0).abs() < 1e-3, "{ease:?}");
+        }
+        assert!(CubicIn.sample(0.25) < 0.25);
+        assert!(CubicOut.sample(0.25) > 0.25);
+        assert!((CubicInOut.sample(0.5) - 0.5).abs() < 1e-6);
+        assert!(BackIn.sample(0.2) < 0.0);
+        assert!(BackOut.sample(0.8) > 1.0);
+    }
+
+    fn headless() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<TweenCompleted>()
+            .add_systems(Update, update_tweens);
+        // Bevy caps each update at 250ms by default
+        app.world.resource_mut::<Time<Virtual>>().set_max_delta(std::time::Duration::from_secs(10));
+        advance(&mut app, 0);
+        app
+    }
+
+    fn x(app: &App, entity: Entity) -> f32 {
+        app.world.get::<Transform>(entity).unwrap().translation.x
+    }
+
+    fn alpha(app: &App, entity: Entity) -> f32 {
+        app.world.get::<Sprite>(entity).unwrap().color.a()
+    }
+
+    fn completed(app: &App) -> Vec<TweenCompleted> {
+        let events = app.world.resource::<Events<TweenCompleted>>();
+        events.get_reader().read(events).cloned().collect()
+    }
+
+    #[test]
+    fn test_sequence_and_parallel() {
+        let mut app = headless();
+        let slide = Tween::translation(Vec3::ZERO, Vec3::X * 100.0, 1.0, Ease::Linear)
+            .with(Tween::sprite_alpha(0.0, 1.0, 0.5, Ease::Linear))
+            .then(Tween::translation(Vec3::X * 100.0, Vec3::X * 200.0, 1.0, Ease::Linear))
+            .with_delay(0.5)
+            .on_complete("done");
+        let entity = app.world.spawn((Transform::default(), Sprite::default(), slide)).id();
+
+        advance(&mut app, 250);
+        assert_eq!((x(&app, entity), alpha(&app, entity)), (0.0, 0.0));
+        advance(&mut app, 500);
+        assert!((x(&app, entity) - 25.0).abs() < 1e-3);
+        assert!((alpha(&app, entity) - 0.5).abs() < 1e-3);
+        advance(&mut app, 1000);
+        assert!((x(&app, entity) - 125.0).abs() < 1e-3);
+        assert_eq!(alpha(&app, entity), 1.0);
+        assert!(completed(&app).is_empty());
+        advance(&mut app, 1000);
+        assert_eq!(x(&app, entity), 200.0);
+        assert!(app.world.get::<Tween>(entity).unwrap().is_finished());
+        assert_eq!(completed(&app), vec![TweenCompleted { entity, name: Some("done".to_string()) }]);
+    }
+
+    #[test]
+    fn test_ping_pong_repeats() {
+        let mut app = headless();
+        let pulse = Tween::scale(Vec3::ONE, Vec3::splat(3.0), 1.0, Ease::Linear)
+            .with_mode(PlaybackMode::PingPong)
+            .with_repeat(TweenRepeat::Times(3));
+        let entity = app.world.spawn((Transform::default(), pulse)).id();
+        let scale = |app: &App| app.world.get::<Transform>(entity).unwrap().scale.x;
+
+        advance(&mut app, 500);
+        assert!((scale(&app) - 2.0).abs() < 1e-3);
+        advance(&mut app, 1000);
+        assert!((scale(&app) - 2.0).abs() < 1e-3);
+        advance(&mut app, 750);
+        assert!((scale(&app) - 1.5).abs() < 1e-3);
+        advance(&mut app, 5000);
+        assert_eq!(scale(&app), 3.0);
+        assert!(app.world.get::<Tween>(entity).unwrap().is_finished());
+    }
+
+    #[test]
+    fn test_text_and_ui_colors() {
+        let mut app = headless();
+        let text = app.world.spawn((
+            Text::from_section("Game Over", TextStyle { color: Color::RED, ..default() }),
+            Tween::text_alpha(0.0, 1.0, 1.0, Ease::Linear),
+        )).id();
+        let button = app.world.spawn((
+            BackgroundColor(Color::BLACK),
+            Tween::ui_color(Color::BLACK, Color::WHITE, 1.0, Ease::Linear).with_mode(PlaybackMode::Reverse),
+        )).id();
+
+        advance(&mut app, 500);
+        let color = app.world.get::<Text>(text).unwrap().sections[0].style.color;
+        assert!((color.a() - 0.5).abs() < 1e-3);
+        assert_eq!(color.r(), 1.0);
+        let background = app.world.get::<BackgroundColor>(button).unwrap().0;
+        assert!((background.r() - 0.5).abs() < 1e-3);
+        advance(&mut app, 500);
+        assert_eq!(app.world.get::<BackgroundColor>(button).unwrap().0.r(), 0.0);
+    }
+}
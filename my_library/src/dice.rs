@@ -0,0 +1,459 @@
+use std::{fmt, str::FromStr};
+
+// Upper bounds that keep a typo like "10000000d6" from stalling a frame.
+const MAX_DICE: u32 = 1_000;
+const MAX_SIDES: u32 = 1_000_000;
+// Guards against pathological explosion chains.
+const MAX_EXPLOSIONS: u32 = 100;
+
+/// Which dice from a group contribute to the total.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiceKeep {
+  /// Every die counts (e.g. `3d6`).
+  All,
+  /// Only the highest `n` dice count (e.g. `2d20kh1`).
+  Highest(u32),
+  /// Only the lowest `n` dice count (e.g. `2d20kl1`).
+  Lowest(u32),
+}
+
+/// A single term in a dice expression: either a group of dice
+/// or a flat modifier.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DiceTerm {
+  /// A group of identical dice, such as `4d6kh3` or `2d10!`.
+  Dice {
+    /// `1` to add the group, `-1` to subtract it.
+    sign: i32,
+    /// How many dice to roll.
+    count: u32,
+    /// How many sides each die has.
+    sides: u32,
+    /// Roll again (and add) whenever a die shows its highest face.
+    exploding: bool,
+    /// Which dice are kept.
+    keep: DiceKeep,
+  },
+  /// A flat modifier, such as `+2` or `-1`.
+  Modifier(i32),
+}
+
+/// A parsed dice expression, such as `3d6+2` or `2d10kh1`.
+///
+/// Parsing once and rolling many times avoids re-parsing the
+/// expression every frame.
+///
+/// The supported notation is a sequence of terms joined by `+` or `-`:
+///
+/// * `NdS` rolls `N` dice with `S` sides. `N` may be omitted (`d20`),
+///   and `d%` is shorthand for `d100`.
+/// * `!` after a group makes the dice explode: a die showing its
+///   highest face is rolled again and added.
+/// * `khN` / `klN` keep the highest or lowest `N` dice of a group.
+///   `kN` is shorthand for `khN`. An exploded die is kept or dropped
+///   whole, ranked by its total.
+/// * A bare number is a flat modifier.
+///
+/// Expressions that could total more than `i32::MAX` (counting every
+/// possible explosion) are rejected when parsed.
+///
+/// ## Example
+///
+/// ```
+/// use my_library::{DiceExpression, RandomNumberGenerator};
+/// let fireball: DiceExpression = "8d6".parse().unwrap();
+/// let mut rng = RandomNumberGenerator::seeded(1);
+/// let damage = rng.roll(&fireball);
+/// assert!(damage.total >= 8 && damage.total <= 48);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiceExpression {
+  terms: Vec<DiceTerm>,
+}
+
+/// One physical die that was rolled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DieRoll {
+  /// The face that came up.
+  pub value: i32,
+  /// Whether this die counts towards the total.
+  pub kept: bool,
+  /// Whether this die was rolled because another one exploded.
+  pub exploded: bool,
+}
+
+/// The outcome of rolling one [`DiceTerm`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiceTermRoll {
+  /// The term that was rolled.
+  pub term: DiceTerm,
+  /// Every die rolled for this term, in the order they were rolled.
+  /// Empty for modifiers.
+  pub rolls: Vec<DieRoll>,
+  /// This term's contribution to the total, including its sign.
+  pub subtotal: i32,
+}
+
+/// The structured result of rolling a [`DiceExpression`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiceRoll {
+  /// The final result.
+  pub total: i32,
+  /// The outcome of each term, in expression order.
+  pub terms: Vec<DiceTermRoll>,
+}
+
+impl DiceRoll {
+  /// Iterates over every die rolled, across all terms.
+  pub fn dice(&self) -> impl Iterator<Item = &DieRoll> {
+    self.terms.iter().flat_map(|t| t.rolls.iter())
+  }
+}
+
+impl DiceExpression {
+  /// Parses a dice expression, returning an error describing the
+  /// first problem found.
+  ///
+  /// # Arguments
+  ///
+  /// * `expression` - the dice notation to parse, e.g. `"3d6+2"`.
+  pub fn parse(expression: &str) -> anyhow::Result<Self> {
+    Parser::new(expression).parse()
+  }
+
+  /// The terms making up this expression.
+  pub fn terms(&self) -> &[DiceTerm] {
+    &self.terms
+  }
+
+  /// Rolls the expression, using `die` to roll a single die with the
+  /// given number of sides (returning a value in `1..=sides`).
+  pub(crate) fn roll_with(&self, mut die: impl FnMut(u32) -> u32) -> DiceRoll {
+    let mut total = 0;
+    let terms = self.terms.iter().map(|term| {
+      let roll = match term {
+        DiceTerm::Modifier(n) => DiceTermRoll {
+          term: term.clone(),
+          rolls: Vec::new(),
+          subtotal: *n,
+        },
+        DiceTerm::Dice { sign, count, sides, exploding, keep } => {
+          let mut rolls = Vec::with_capacity(*count as usize);
+          for _ in 0..*count {
+            let mut value = die(*sides);
+            rolls.push(DieRoll { value: value as i32, kept: true, exploded: false });
+            let mut explosions = 0;
+            while *exploding && value == *sides && explosions < MAX_EXPLOSIONS {
+              value = die(*sides);
+              rolls.push(DieRoll { value: value as i32, kept: true, exploded: true });
+              explosions += 1;
+            }
+          }
+          apply_keep(&mut rolls, *keep);
+          let subtotal = sign * rolls.iter()
+            .filter(|r| r.kept)
+            .map(|r| r.value)
+            .sum::<i32>();
+          DiceTermRoll { term: term.clone(), rolls, subtotal }
+        }
+      };
+      total += roll.subtotal;
+      roll
+    }).collect();
+    DiceRoll { total, terms }
+  }
+}
+
+fn apply_keep(rolls: &mut [DieRoll], keep: DiceKeep) {
+  let (n, highest) = match keep {
+    DiceKeep::All => return,
+    DiceKeep::Highest(n) => (n as usize, true),
+    DiceKeep::Lowest(n) => (n as usize, false),
+  };
+  // An exploding die and the rolls it added count as one die, worth
+  // their sum: (first roll, one past its last roll, value)
+  let mut chains: Vec<(usize, usize, i32)> = Vec::new();
+  for (i, roll) in rolls.iter().enumerate() {
+    match chains.last_mut() {
+      Some((_, end, value)) if roll.exploded => {
+        *end = i + 1;
+        *value += roll.value;
+      }
+      _ => chains.push((i, i + 1, roll.value)),
+    }
+  }
+  // Sort indices rather than the rolls, so the caller still sees
+  // the dice in the order they were thrown.
+  let mut order: Vec<usize> = (0..chains.len()).collect();
+  order.sort_by_key(|&i| chains[i].2);
+  if highest {
+    order.reverse();
+  }
+  for (rank, i) in order.into_iter().enumerate() {
+    let (start, end, _) = chains[i];
+    for roll in &mut rolls[start..end] {
+      roll.kept = rank < n;
+    }
+  }
+}
+
+impl FromStr for DiceExpression {
+  type Err = anyhow::Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Self::parse(s)
+  }
+}
+
+impl fmt::Display for DiceExpression {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (i, term) in self.terms.iter().enumerate() {
+      let (sign, body) = match term {
+        DiceTerm::Modifier(n) => (n.signum(), n.unsigned_abs().to_string()),
+        DiceTerm::Dice { sign, count, sides, exploding, keep } => {
+          let mut body = format!("{count}d{sides}");
+          if *exploding {
+            body.push('!');
+          }
+          match keep {
+            DiceKeep::All => {}
+            DiceKeep::Highest(n) => body.push_str(&format!("kh{n}")),
+            DiceKeep::Lowest(n) => body.push_str(&format!("kl{n}")),
+          }
+          (*sign, body)
+        }
+      };
+      if sign < 0 {
+        write!(f, "-")?;
+      } else if i > 0 {
+        write!(f, "+")?;
+      }
+      write!(f, "{body}")?;
+    }
+    Ok(())
+  }
+}
+
+struct Parser<'a> {
+  source: &'a str,
+  chars: Vec<char>,
+  pos: usize,
+}
+
+impl<'a> Parser<'a> {
+  fn new(source: &'a str) -> Self {
+    Self {
+      source,
+      chars: source.chars().filter(|c| !c.is_whitespace()).collect(),
+      pos: 0,
+    }
+  }
+
+  fn error<T>(&self, message: impl fmt::Display) -> anyhow::Result<T> {
+    Err(anyhow::Error::msg(format!(
+      "invalid dice expression \"{}\": {message}",
+      self.source
+    )))
+  }
+
+  fn peek(&self) -> Option<char> {
+    self.chars.get(self.pos).copied()
+  }
+
+  fn eat(&mut self, c: char) -> bool {
+    if self.peek().map(|p| p.to_ascii_lowercase()) == Some(c) {
+      self.pos += 1;
+      true
+    } else {
+      false
+    }
+  }
+
+  fn number(&mut self) -> anyhow::Result<Option<u32>> {
+    let start = self.pos;
+    while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+      self.pos += 1;
+    }
+    if start == self.pos {
+      return Ok(None);
+    }
+    let digits: String = self.chars[start..self.pos].iter().collect();
+    match digits.parse() {
+      Ok(n) => Ok(Some(n)),
+      Err(_) => self.error(format!("{digits} is too large")),
+    }
+  }
+
+  fn parse(mut self) -> anyhow::Result<DiceExpression> {
+    if self.chars.is_empty() {
+      return self.error("expression is empty");
+    }
+    let mut terms = Vec::new();
+    let mut sign = if self.eat('-') { -1 } else { self.eat('+'); 1 };
+    loop {
+      terms.push(self.term(sign)?);
+      if self.eat('+') {
+        sign = 1;
+      } else if self.eat('-') {
+        sign = -1;
+      } else if let Some(c) = self.peek() {
+        return self.error(format!("unexpected '{c}'"));
+      } else {
+        break;
+      }
+    }
+    // Rolling adds up in i32, so refuse anything that could overflow
+    // it, even with every die (and every explosion) at its highest
+    let largest: u64 = terms.iter().map(largest_magnitude).sum();
+    if largest > i32::MAX as u64 {
+      return self.error(format!("could total {largest}, more than {}", i32::MAX));
+    }
+    Ok(DiceExpression { terms })
+  }
+
+  fn term(&mut self, sign: i32) -> anyhow::Result<DiceTerm> {
+    let count = self.number()?;
+    if !self.eat('d') {
+      return match count {
+        Some(n) => match i32::try_from(n) {
+          Ok(n) => Ok(DiceTerm::Modifier(sign * n)),
+          Err(_) => self.error(format!("modifier {n} is too large")),
+        },
+        None => match self.peek() {
+          Some(c) => self.error(format!("expected a number or dice, found '{c}'")),
+          None => self.error("expression ends with an operator"),
+        },
+      };
+    }
+    let count = count.unwrap_or(1);
+    let sides = if self.eat('%') {
+      100
+    } else {
+      match self.number()? {
+        Some(n) => n,
+        None => return self.error("missing number of sides after 'd'"),
+      }
+    };
+    if count == 0 || count > MAX_DICE {
+      return self.error(format!("dice count must be between 1 and {MAX_DICE}"));
+    }
+    if sides == 0 || sides > MAX_SIDES {
+      return self.error(format!("dice sides must be between 1 and {MAX_SIDES}"));
+    }
+
+    let exploding = self.eat('!');
+    if exploding && sides == 1 {
+      return self.error("a one-sided die cannot explode");
+    }
+
+    let keep = if self.eat('k') {
+      let lowest = if self.eat('l') { true } else { self.eat('h'); false };
+      let n = match self.number()? {
+        Some(n) => n,
+        None => return self.error("missing number of dice to keep"),
+      };
+      if n == 0 || n > count {
+        return self.error(format!("cannot keep {n} of {count} dice"));
+      }
+      if lowest { DiceKeep::Lowest(n) } else { DiceKeep::Highest(n) }
+    } else {
+      DiceKeep::All
+    };
+
+    Ok(DiceTerm::Dice { sign, count, sides, exploding, keep })
+  }
+}
+
+// The largest absolute value `term` can contribute
+fn largest_magnitude(term: &DiceTerm) -> u64 {
+  match term {
+    DiceTerm::Modifier(n) => n.unsigned_abs() as u64,
+    DiceTerm::Dice { count, sides, exploding, keep, .. } => {
+      // A kept die brings its whole explosion chain with it
+      let per_die = *sides as u64 * if *exploding { 1 + MAX_EXPLOSIONS as u64 } else { 1 };
+      let kept = match keep {
+        DiceKeep::All => *count,
+        DiceKeep::Highest(n) | DiceKeep::Lowest(n) => *n,
+      };
+      kept as u64 * per_die
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_parse_simple() {
+    let expr = DiceExpression::parse("3d6+2").unwrap();
+    assert_eq!(expr.terms(), &[
+      DiceTerm::Dice { sign: 1, count: 3, sides: 6, exploding: false, keep: DiceKeep::All },
+      DiceTerm::Modifier(2),
+    ]);
+  }
+
+  #[test]
+  fn test_parse_modifiers() {
+    let expr = DiceExpression::parse(" -d% + 4d6!kl2 - 1 ").unwrap();
+    assert_eq!(expr.terms(), &[
+      DiceTerm::Dice { sign: -1, count: 1, sides: 100, exploding: false, keep: DiceKeep::All },
+      DiceTerm::Dice { sign: 1, count: 4, sides: 6, exploding: true, keep: DiceKeep::Lowest(2) },
+      DiceTerm::Modifier(-1),
+    ]);
+    assert_eq!(expr.to_string(), "-1d100+4d6!kl2-1");
+  }
+
+  #[test]
+  fn test_parse_errors() {
+    for bad in ["", "3d", "d6+", "2d6kh3", "0d6", "1d0", "1d1!", "2d6x", "3d6kh"] {
+      assert!(DiceExpression::parse(bad).is_err(), "{bad} should not parse");
+    }
+  }
+
+  #[test]
+  fn test_totals_fit_in_i32() {
+    for bad in ["2147483647+1", "-2147483647-2", "1000d1000000+1000d1000000+1000d1000000", "1000d100000!", "1000d100000!kh500"] {
+      let error = DiceExpression::parse(bad).unwrap_err().to_string();
+      assert!(error.contains("more than 2147483647"), "{bad}: {error}");
+    }
+    assert!(DiceExpression::parse("2147483647").is_ok());
+    assert!(DiceExpression::parse("1000d1000000+1000d1000000").is_ok());
+    // Keeping a few dice caps the total, even counting their explosions
+    assert!(DiceExpression::parse("1000d100000!kh1").is_ok());
+  }
+
+  #[test]
+  fn test_keep_highest() {
+    let expr = DiceExpression::parse("3d6kh2").unwrap();
+    let mut faces = [2, 6, 4].into_iter();
+    let roll = expr.roll_with(|_| faces.next().unwrap());
+    assert_eq!(roll.total, 10);
+    let kept: Vec<bool> = roll.dice().map(|d| d.kept).collect();
+    assert_eq!(kept, vec![false, true, true]);
+  }
+
+  #[test]
+  fn test_exploding() {
+    let expr = DiceExpression::parse("2d6!").unwrap();
+    let mut faces = [6, 6, 3, 1].into_iter();
+    let roll = expr.roll_with(|_| faces.next().unwrap());
+    assert_eq!(roll.total, 16);
+    let exploded: Vec<bool> = roll.dice().map(|d| d.exploded).collect();
+    assert_eq!(exploded, vec![false, true, true, false]);
+  }
+  #[test]
+  fn test_keep_exploding() {
+    let expr = DiceExpression::parse("4d6!kh3").unwrap();
+    let mut faces = [6, 2, 5, 1, 3].into_iter();
+    let roll = expr.roll_with(|_| faces.next().unwrap());
+    // 6+2, 5 and 3 are kept; the 1 is dropped
+    assert_eq!(roll.total, 16);
+    let kept: Vec<bool> = roll.dice().map(|d| d.kept).collect();
+    assert_eq!(kept, vec![true, true, true, false, true]);
+
+    let expr = DiceExpression::parse("2d6!kh1").unwrap();
+    let mut faces = [6, 6, 3, 1].into_iter();
+    assert_eq!(expr.roll_with(|_| faces.next().unwrap()).total, 15);
+  }
+}
@@ -0,0 +1,33 @@
+//! Builds the `rand_distr` distributions behind the non-uniform sampling
+//! methods on `RandomNumberGenerator`, shared by the plain and locking
+//! variants. Invalid parameters are programming errors, so they panic
+//! with a message naming the method, just as `range` panics on an empty
+//! range.
+use rand_distr::{Beta, Binomial, Exp, Normal, Poisson, Triangular};
+
+pub(crate) fn normal(mean: f32, std_dev: f32) -> Normal<f32> {
+  Normal::new(mean, std_dev)
+    .expect("normal: std_dev must be finite")
+}
+
+pub(crate) fn exponential(rate: f32) -> Exp<f32> {
+  Exp::new(rate).expect("exponential: rate must be non-negative")
+}
+
+pub(crate) fn poisson(mean: f32) -> Poisson<f32> {
+  Poisson::new(mean).expect("poisson: mean must be positive and finite")
+}
+
+pub(crate) fn binomial(trials: u64, probability: f64) -> Binomial {
+  Binomial::new(trials, probability)
+    .expect("binomial: probability must be between 0 and 1")
+}
+
+pub(crate) fn triangular(min: f32, max: f32, mode: f32) -> Triangular<f32> {
+  Triangular::new(min, max, mode)
+    .expect("triangular: requires min <= mode <= max")
+}
+
+pub(crate) fn beta(alpha: f32, beta: f32) -> Beta<f32> {
+  Beta::new(alpha, beta).expect("beta: alpha and beta must be positive")
+}
@@ -6,6 +6,23 @@
 //! `my_library` includes:(3)
 //! 
 //! * Random number generation facilities.(4)
+//! * Non-uniform distributions (normal, exponential, Poisson, binomial,
+//!   triangular and beta).
+//! * Shuffling, random choice and sampling, plus a "Tetris bag" that
+//!   deals items fairly.
+//! * Random points in circles, rings, rectangles and polygons, random
+//!   directions, and Poisson-disc placement.
+//! * Independent, deterministic random streams per system or entity.
+//! * Stateless, position-based random values for endless or streamed
+//!   worlds.
+//! * Seeding policies (fixed, environment variable, command line or
+//!   daily) so any run can be replayed.
+//! * Coherent noise (Perlin, simplex, value, fBm and domain warping).
+//! * Map generation (cellular automata caves, drunkard's walk tunnels,
+//!   BSP rooms and side-view height maps) in [`mapgen`].
+//! * Dice notation parsing and rolling (`"3d6+2"`, `"2d20kh1"`).
+//! * Weighted random tables, for loot and spawn odds.
+//! * Markov chain name and text generation, trained from word lists.
 //! 
 //! ## Feature Flags
 //! 
@@ -16,11 +33,27 @@
 //! * The `locking` feature enables interior mutability inside 
 //! [`RandomNumberGenerator`], (5)
 //!   allowing it to be used as a resource (`Res<RandomNumberGenerator`) 
-//! rather than requiring mutability (`ResMut<RandomNumberGenerator>`)
+//! rather than requiring mutability (`ResMut<RandomNumberGenerator>`).
+//!   Busy parallel systems can take a per-thread sub-generator with
+//!   `RandomNumberGenerator::per_thread` to avoid lock contention.
 //! * You can control which random number generation algorithm is used by 
-//! specifying *one* of:
-//!    * `xorshift` to use the XorShift algorithm.
+//! default by specifying one of:
+//!    * `xorshift` to use the XorShift algorithm. `pcg` is a default
+//!      feature and wins if both are enabled, so also set
+//!      `default-features = false`.
 //!    * `pcg` to use the PCG algorithm.
+//!    * Neither, to use ChaCha12 (the algorithm behind `rand`'s `StdRng`).
+//!
+//!   Every algorithm is compiled in, and can be picked at runtime with
+//!   `RandomNumberGenerator::with_algorithm`.
+
+mod algorithm;
+pub use algorithm::Algorithm;
+
+mod distributions;
+mod sampling;
+mod random_state;
+pub use random_state::RandomState;
 
 #[cfg(not(feature = "locking"))]
 mod random;
@@ -32,6 +65,32 @@ mod random_locking;
 #[cfg(feature = "locking")]
 pub use random_locking::*;
 
+mod random_streams;
+pub use random_streams::RandomStreams;
+
+mod coordinate_random;
+pub use coordinate_random::{CoordinateRandom, CoordinateRng};
+
+mod random_plugin;
+pub use random_plugin::{RandomPlugin, RandomSeed, ReseedRandom, SeedPolicy};
+
+mod shuffle_bag;
+pub use shuffle_bag::ShuffleBag;
+
+mod noise;
+pub use noise::*;
+
+pub mod mapgen;
+
+mod markov;
+pub use markov::*;
+
+mod dice;
+pub use dice::*;
+
+mod weighted_table;
+pub use weighted_table::*;
+
 /// [`RandomNumberGenerator`] wraps the `rand` crate. The `rand` crate
 /// is re-exported for your convenience.
 pub mod rand {
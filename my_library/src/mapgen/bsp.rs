@@ -0,0 +1,163 @@
+use bevy::math::{IRect, IVec2};
+use rand::Rng;
+use super::{Grid, Tile};
+
+/// Rooms-and-corridors generation with binary space partitioning: the
+/// map is split in two, again and again, until the pieces are small;
+/// each piece gets a room, and the two halves of every split are
+/// joined with a corridor. Every room is reachable.
+///
+/// ## Example
+///
+/// ```
+/// use my_library::{RandomNumberGenerator, mapgen::{Bsp, Tile}};
+/// let mut rng = RandomNumberGenerator::seeded(1);
+/// let map = Bsp::default().generate(&mut rng);
+/// let first_room = map.rooms[0];
+/// assert_eq!(map.grid[first_room.center()], Tile::Floor);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bsp {
+  /// Width of the map, in cells.
+  pub width: i32,
+  /// Height of the map, in cells.
+  pub height: i32,
+  /// The smallest width or height of a room.
+  pub min_room: i32,
+  /// The largest width or height of a room.
+  pub max_room: i32,
+}
+
+impl Default for Bsp {
+  fn default() -> Self {
+    Self {
+      width: 80,
+      height: 50,
+      min_room: 4,
+      max_room: 12,
+    }
+  }
+}
+
+/// The result of [`Bsp::generate`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BspMap {
+  /// The map itself.
+  pub grid: Grid<Tile>,
+  /// Every room, with `min` and `max` both inside the room. Handy for
+  /// placing the player, treasure or monsters.
+  pub rooms: Vec<IRect>,
+}
+
+impl Bsp {
+  /// Generates a rooms-and-corridors map.
+  ///
+  /// # Panics
+  ///
+  /// Panics unless `1 <= min_room <= max_room`.
+  pub fn generate<R: Rng + ?Sized>(&self, rng: &mut R) -> BspMap {
+    assert!(
+      1 <= self.min_room && self.min_room <= self.max_room,
+      "Bsp: requires 1 <= min_room <= max_room"
+    );
+    let mut map = BspMap {
+      grid: Grid::new(self.width, self.height, Tile::Wall),
+      rooms: Vec::new(),
+    };
+    // Leaves include a one cell wall on every side
+    let area = IRect::new(0, 0, self.width, self.height);
+    if area.width() >= self.min_room + 2 && area.height() >= self.min_room + 2 {
+      self.split(area, rng, &mut map);
+    }
+    map
+  }
+
+  // Returns a room inside `area`, to connect corridors to.
+  fn split<R: Rng + ?Sized>(&self, area: IRect, rng: &mut R, map: &mut BspMap) -> IRect {
+    let min_leaf = self.min_room + 2;
+    let can_split_x = area.width() >= min_leaf * 2;
+    let can_split_y = area.height() >= min_leaf * 2;
+    let too_big = area.width() > self.max_room + 2 || area.height() > self.max_room + 2;
+    // Stop at some point once rooms would fit, so room sizes vary
+    let stop = !too_big && rng.gen_range(0..3) == 0;
+    if stop || !(can_split_x || can_split_y) {
+      return self.room(area, rng, map);
+    }
+    let split_x = if can_split_x && can_split_y {
+      area.width() > area.height() || (area.width() == area.height() && rng.gen())
+    } else {
+      can_split_x
+    };
+    let (a, b) = if split_x {
+      let x = rng.gen_range(area.min.x + min_leaf..=area.max.x - min_leaf);
+      (IRect::new(area.min.x, area.min.y, x, area.max.y), IRect::new(x, area.min.y, area.max.x, area.max.y))
+    } else {
+      let y = rng.gen_range(area.min.y + min_leaf..=area.max.y - min_leaf);
+      (IRect::new(area.min.x, area.min.y, area.max.x, y), IRect::new(area.min.x, y, area.max.x, area.max.y))
+    };
+    let room_a = self.split(a, rng, map);
+    let room_b = self.split(b, rng, map);
+    corridor(&mut map.grid, room_a.center(), room_b.center(), rng);
+    if rng.gen() { room_a } else { room_b }
+  }
+
+  fn room<R: Rng + ?Sized>(&self, leaf: IRect, rng: &mut R, map: &mut BspMap) -> IRect {
+    let width = rng.gen_range(self.min_room..=self.max_room.min(leaf.width() - 2));
+    let height = rng.gen_range(self.min_room..=self.max_room.min(leaf.height() - 2));
+    let x = rng.gen_range(leaf.min.x + 1..=leaf.max.x - 1 - width);
+    let y = rng.gen_range(leaf.min.y + 1..=leaf.max.y - 1 - height);
+    let room = IRect::new(x, y, x + width - 1, y + height - 1);
+    for y in room.min.y..=room.max.y {
+      for x in room.min.x..=room.max.x {
+        map.grid[IVec2::new(x, y)] = Tile::Floor;
+      }
+    }
+    map.rooms.push(room);
+    room
+  }
+}
+
+// An L-shaped corridor, turning horizontally or vertically first.
+fn corridor<R: Rng + ?Sized>(grid: &mut Grid<Tile>, from: IVec2, to: IVec2, rng: &mut R) {
+  let corner = if rng.gen() { IVec2::new(to.x, from.y) } else { IVec2::new(from.x, to.y) };
+  for (start, end) in [(from, corner), (corner, to)] {
+    let min = start.min(end);
+    let max = start.max(end);
+    for y in min.y..=max.y {
+      for x in min.x..=max.x {
+        grid[IVec2::new(x, y)] = Tile::Floor;
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::RandomNumberGenerator;
+
+  #[test]
+  fn test_rooms_are_connected() {
+    for seed in 0..20 {
+      let settings = Bsp::default();
+      let map = settings.generate(&mut RandomNumberGenerator::seeded(seed));
+      assert!(map.rooms.len() >= 4);
+      let reachable = map.grid.flood_fill(map.rooms[0].center(), |t| *t == Tile::Floor);
+      for room in &map.rooms {
+        assert!(room.width() + 1 >= settings.min_room && room.width() < settings.max_room);
+        assert!(room.min.x > 0 && room.min.y > 0);
+        assert!(room.max.x < settings.width - 1 && room.max.y < settings.height - 1);
+        assert!(reachable.contains(&room.center()));
+      }
+    }
+  }
+
+  #[test]
+  fn test_small_maps() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let tiny = Bsp { width: 5, height: 5, ..Default::default() }.generate(&mut rng);
+    assert!(tiny.rooms.is_empty());
+    let one = Bsp { width: 6, height: 6, ..Default::default() }.generate(&mut rng);
+    assert_eq!(one.rooms.len(), 1);
+  }
+}
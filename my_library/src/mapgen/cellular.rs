@@ -0,0 +1,86 @@
+use bevy::math::IVec2;
+use rand::Rng;
+use super::{Grid, Tile};
+
+/// Cave generation with cellular automata: start from random noise,
+/// then repeatedly turn each cell into a wall if most of its
+/// neighbours are walls. The result is organic, twisty caverns.
+///
+/// The border is always wall, and caves aren't guaranteed to be
+/// connected---use [`Grid::keep_connected`] if that matters.
+///
+/// ## Example
+///
+/// ```
+/// use my_library::{RandomNumberGenerator, mapgen::CellularAutomata};
+/// let mut rng = RandomNumberGenerator::seeded(1);
+/// let caves = CellularAutomata { width: 64, height: 48, ..Default::default() }
+///   .generate(&mut rng);
+/// assert_eq!(caves.width(), 64);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CellularAutomata {
+  /// Width of the map, in cells.
+  pub width: i32,
+  /// Height of the map, in cells.
+  pub height: i32,
+  /// The chance (`0.0..=1.0`) that each cell starts as a wall. Around
+  /// 0.45 gives caves; lower values open them up.
+  pub wall_chance: f32,
+  /// How many smoothing passes to run. More passes give smoother walls.
+  pub iterations: u32,
+}
+
+impl Default for CellularAutomata {
+  fn default() -> Self {
+    Self {
+      width: 80,
+      height: 50,
+      wall_chance: 0.45,
+      iterations: 5,
+    }
+  }
+}
+
+impl CellularAutomata {
+  /// Generates a cave map.
+  pub fn generate<R: Rng + ?Sized>(&self, rng: &mut R) -> Grid<Tile> {
+    let is_border = |pos: IVec2| {
+      pos.x == 0 || pos.y == 0 || pos.x == self.width - 1 || pos.y == self.height - 1
+    };
+    let mut grid = Grid::from_fn(self.width, self.height, |pos| {
+      if is_border(pos) || rng.gen_range(0.0..1.0) < self.wall_chance {
+        Tile::Wall
+      } else {
+        Tile::Floor
+      }
+    });
+    for _ in 0..self.iterations {
+      grid = grid.map(|pos, tile| {
+        let walls = grid.count_neighbours(pos, true, |t| *t == Tile::Wall)
+          + usize::from(*tile == Tile::Wall);
+        if is_border(pos) || walls >= 5 { Tile::Wall } else { Tile::Floor }
+      });
+    }
+    grid
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::RandomNumberGenerator;
+
+  #[test]
+  fn test_caves() {
+    let settings = CellularAutomata::default();
+    let caves = settings.generate(&mut RandomNumberGenerator::seeded(1));
+    let floor = caves.iter().filter(|(_, t)| **t == Tile::Floor).count();
+    let total = (settings.width * settings.height) as usize;
+    assert!(floor > total / 4 && floor < total * 3 / 4);
+    assert!(caves.iter().all(|(pos, tile)| {
+      *tile == Tile::Wall || (pos.x > 0 && pos.y > 0 && pos.x < 79 && pos.y < 49)
+    }));
+    assert_eq!(caves, settings.generate(&mut RandomNumberGenerator::seeded(1)));
+  }
+}
@@ -0,0 +1,101 @@
+use bevy::math::IVec2;
+use rand::{Rng, seq::SliceRandom};
+use super::{Grid, Tile};
+
+/// Tunnel generation with a "drunkard's walk": a digger staggers
+/// around at random, carving floor wherever it goes. When it tires,
+/// a new one starts from somewhere already dug, until enough of the
+/// map is open. The result is winding, always-connected tunnels.
+///
+/// ## Example
+///
+/// ```
+/// use my_library::{RandomNumberGenerator, mapgen::{DrunkardsWalk, Tile}};
+/// let mut rng = RandomNumberGenerator::seeded(1);
+/// let map = DrunkardsWalk::default().generate(&mut rng);
+/// let floor = map.iter().filter(|(_, t)| **t == Tile::Floor).count();
+/// // 40% of the map, inside its border
+/// assert!(floor >= 78 * 48 * 4 / 10);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DrunkardsWalk {
+  /// Width of the map, in cells.
+  pub width: i32,
+  /// Height of the map, in cells.
+  pub height: i32,
+  /// How much of the map (`0.0..=1.0`) to dig out before stopping.
+  pub floor: f32,
+  /// How many steps each digger takes before giving up.
+  pub lifetime: u32,
+}
+
+impl Default for DrunkardsWalk {
+  fn default() -> Self {
+    Self {
+      width: 80,
+      height: 50,
+      floor: 0.4,
+      lifetime: 400,
+    }
+  }
+}
+
+impl DrunkardsWalk {
+  /// Generates a tunnel map. The border is never dug, and digging
+  /// starts from the centre of the map.
+  pub fn generate<R: Rng + ?Sized>(&self, rng: &mut R) -> Grid<Tile> {
+    let mut grid = Grid::new(self.width, self.height, Tile::Wall);
+    let interior = ((self.width - 2).max(0) * (self.height - 2).max(0)) as usize;
+    let target = (interior as f32 * self.floor.clamp(0.0, 1.0)) as usize;
+    let diggable = |pos: IVec2| {
+      pos.x > 0 && pos.y > 0 && pos.x < self.width - 1 && pos.y < self.height - 1
+    };
+    let mut dug: Vec<IVec2> = Vec::new();
+    let mut start = IVec2::new(self.width / 2, self.height / 2);
+    while dug.len() < target {
+      let mut pos = start;
+      let mut dig = |pos: IVec2| {
+        if grid[pos] == Tile::Wall {
+          grid[pos] = Tile::Floor;
+          dug.push(pos);
+        }
+      };
+      dig(pos);
+      for _ in 0..self.lifetime.max(1) {
+        let step = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y][rng.gen_range(0..4)];
+        if diggable(pos + step) {
+          pos += step;
+        }
+        dig(pos);
+      }
+      start = *dug.choose(rng).unwrap();
+    }
+    grid
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::RandomNumberGenerator;
+
+  #[test]
+  fn test_tunnels_are_connected() {
+    let settings = DrunkardsWalk { width: 40, height: 30, ..Default::default() };
+    let map = settings.generate(&mut RandomNumberGenerator::seeded(1));
+    let floor = map.iter().filter(|(_, t)| **t == Tile::Floor).count();
+    assert!(floor >= 38 * 28 * 4 / 10);
+    let reachable = map.flood_fill(IVec2::new(20, 15), |t| *t == Tile::Floor);
+    assert_eq!(reachable.len(), floor);
+    assert!(map.iter().all(|(pos, tile)| {
+      *tile == Tile::Wall || (pos.x > 0 && pos.y > 0 && pos.x < 39 && pos.y < 29)
+    }));
+  }
+
+  #[test]
+  fn test_tiny_map() {
+    let map = DrunkardsWalk { width: 2, height: 2, ..Default::default() }
+      .generate(&mut RandomNumberGenerator::seeded(1));
+    assert!(map.iter().all(|(_, t)| *t == Tile::Wall));
+  }
+}
@@ -0,0 +1,223 @@
+use bevy::math::{IVec2, Vec2};
+use std::ops::{Index, IndexMut};
+
+/// What a map generator puts in each cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum Tile {
+  /// Solid rock: generators start from an all-wall map.
+  #[default]
+  Wall,
+  /// Open space.
+  Floor,
+}
+
+/// A rectangular grid of cells, addressed by `IVec2` positions.
+///
+/// `(0, 0)` is the bottom-left cell, and `y` grows upwards---the same
+/// way as Bevy's world coordinates---so a grid can be turned into
+/// sprites without flipping it.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::math::{IVec2, Vec2};
+/// use my_library::mapgen::{Grid, Tile};
+/// let mut grid = Grid::new(4, 3, Tile::Wall);
+/// grid[IVec2::new(1, 1)] = Tile::Floor;
+/// assert_eq!(grid.get(IVec2::new(1, 1)), Some(&Tile::Floor));
+/// assert_eq!(grid.get(IVec2::new(4, 0)), None);
+/// // 16 pixel tiles, centred on the world origin
+/// assert_eq!(grid.to_world(IVec2::new(0, 0), 16.0), Vec2::new(-24.0, -16.0));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+  width: i32,
+  height: i32,
+  cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+  /// Creates a grid with every cell set to `fill`.
+  pub fn new(width: i32, height: i32, fill: T) -> Self {
+    let (width, height) = (width.max(0), height.max(0));
+    Self {
+      width,
+      height,
+      cells: vec![fill; (width * height) as usize],
+    }
+  }
+}
+
+impl<T> Grid<T> {
+  /// Creates a grid by calling `f` for each position.
+  pub fn from_fn(width: i32, height: i32, mut f: impl FnMut(IVec2) -> T) -> Self {
+    let (width, height) = (width.max(0), height.max(0));
+    let cells = (0..width * height)
+      .map(|i| f(IVec2::new(i % width, i / width)))
+      .collect();
+    Self { width, height, cells }
+  }
+
+  /// Width of the grid, in cells.
+  pub fn width(&self) -> i32 {
+    self.width
+  }
+
+  /// Height of the grid, in cells.
+  pub fn height(&self) -> i32 {
+    self.height
+  }
+
+  /// Is `pos` inside the grid?
+  pub fn in_bounds(&self, pos: IVec2) -> bool {
+    pos.x >= 0 && pos.y >= 0 && pos.x < self.width && pos.y < self.height
+  }
+
+  fn index_of(&self, pos: IVec2) -> Option<usize> {
+    self.in_bounds(pos).then(|| (pos.y * self.width + pos.x) as usize)
+  }
+
+  /// The cell at `pos`, or `None` if it is outside the grid.
+  pub fn get(&self, pos: IVec2) -> Option<&T> {
+    self.index_of(pos).map(|i| &self.cells[i])
+  }
+
+  /// The cell at `pos`, or `None` if it is outside the grid.
+  pub fn get_mut(&mut self, pos: IVec2) -> Option<&mut T> {
+    self.index_of(pos).map(|i| &mut self.cells[i])
+  }
+
+  /// Every position in the grid, row by row from the bottom.
+  pub fn positions(&self) -> impl Iterator<Item = IVec2> {
+    let width = self.width;
+    (0..self.cells.len() as i32).map(move |i| IVec2::new(i % width, i / width))
+  }
+
+  /// Every cell with its position, row by row from the bottom.
+  pub fn iter(&self) -> impl Iterator<Item = (IVec2, &T)> {
+    self.positions().zip(self.cells.iter())
+  }
+
+  /// Creates a grid of the same size by converting every cell.
+  pub fn map<U>(&self, mut f: impl FnMut(IVec2, &T) -> U) -> Grid<U> {
+    Grid {
+      width: self.width,
+      height: self.height,
+      cells: self.iter().map(|(pos, cell)| f(pos, cell)).collect(),
+    }
+  }
+
+  /// Counts the 8 neighbours of `pos` that match `predicate`.
+  /// Neighbours outside the grid count if `outside` is true.
+  pub fn count_neighbours(&self, pos: IVec2, outside: bool, predicate: impl Fn(&T) -> bool) -> usize {
+    NEIGHBOURS_8
+      .iter()
+      .filter(|offset| self.get(pos + **offset).map_or(outside, &predicate))
+      .count()
+  }
+
+  /// Finds every position reachable from `start` by stepping
+  /// up, down, left and right through cells that match `passable`.
+  /// Returns an empty list if `start` itself isn't passable.
+  pub fn flood_fill(&self, start: IVec2, passable: impl Fn(&T) -> bool) -> Vec<IVec2> {
+    let mut seen = vec![false; self.cells.len()];
+    let mut found = Vec::new();
+    let mut open = Vec::new();
+    if let Some(i) = self.index_of(start).filter(|i| passable(&self.cells[*i])) {
+      seen[i] = true;
+      open.push(start);
+    }
+    while let Some(pos) = open.pop() {
+      found.push(pos);
+      for offset in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+        let next = pos + offset;
+        if let Some(i) = self.index_of(next) {
+          if !seen[i] && passable(&self.cells[i]) {
+            seen[i] = true;
+            open.push(next);
+          }
+        }
+      }
+    }
+    found
+  }
+
+  /// The world position of the centre of cell `pos`, with cells
+  /// `tile_size` pixels across and the grid centred on the origin.
+  /// Use it to place sprites, e.g. with `spawn_image!`.
+  pub fn to_world(&self, pos: IVec2, tile_size: f32) -> Vec2 {
+    let size = Vec2::new(self.width as f32, self.height as f32);
+    (pos.as_vec2() + 0.5 - size / 2.0) * tile_size
+  }
+
+  /// The cell containing world position `point`; the reverse of
+  /// [`Grid::to_world`]. The result may be outside the grid.
+  pub fn from_world(&self, point: Vec2, tile_size: f32) -> IVec2 {
+    let size = Vec2::new(self.width as f32, self.height as f32);
+    (point / tile_size + size / 2.0).floor().as_ivec2()
+  }
+}
+
+impl Grid<Tile> {
+  /// Fills in every floor cell that can't be reached from `start`, so
+  /// the map has no sealed-off pockets.
+  pub fn keep_connected(&mut self, start: IVec2) {
+    let mut reachable = Grid::new(self.width, self.height, false);
+    for pos in self.flood_fill(start, |tile| *tile == Tile::Floor) {
+      reachable[pos] = true;
+    }
+    for (cell, reachable) in self.cells.iter_mut().zip(reachable.cells) {
+      if !reachable {
+        *cell = Tile::Wall;
+      }
+    }
+  }
+}
+
+impl<T> Index<IVec2> for Grid<T> {
+  type Output = T;
+
+  fn index(&self, pos: IVec2) -> &T {
+    self.get(pos).expect("grid position out of bounds")
+  }
+}
+
+impl<T> IndexMut<IVec2> for Grid<T> {
+  fn index_mut(&mut self, pos: IVec2) -> &mut T {
+    self.get_mut(pos).expect("grid position out of bounds")
+  }
+}
+
+const NEIGHBOURS_8: [IVec2; 8] = [
+  IVec2::new(-1, -1), IVec2::new(0, -1), IVec2::new(1, -1),
+  IVec2::new(-1, 0), IVec2::new(1, 0),
+  IVec2::new(-1, 1), IVec2::new(0, 1), IVec2::new(1, 1),
+];
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_world_round_trip() {
+    let grid = Grid::new(64, 48, Tile::Wall);
+    for pos in grid.positions() {
+      assert_eq!(grid.from_world(grid.to_world(pos, 16.0), 16.0), pos);
+    }
+    assert_eq!(grid.from_world(Vec2::new(-512.0, -384.0), 16.0), IVec2::ZERO);
+  }
+
+  #[test]
+  fn test_flood_fill() {
+    // Two rooms, split by a wall in column 2
+    let mut grid = Grid::from_fn(5, 3, |pos| {
+      if pos.x == 2 { Tile::Wall } else { Tile::Floor }
+    });
+    assert_eq!(grid.flood_fill(IVec2::ZERO, |t| *t == Tile::Floor).len(), 6);
+    assert!(grid.flood_fill(IVec2::new(2, 0), |t| *t == Tile::Floor).is_empty());
+    grid.keep_connected(IVec2::ZERO);
+    assert_eq!(grid.iter().filter(|(_, t)| **t == Tile::Floor).count(), 6);
+    assert_eq!(grid[IVec2::new(4, 2)], Tile::Wall);
+    assert_eq!(grid.count_neighbours(IVec2::new(0, 1), true, |t| *t == Tile::Wall), 3);
+  }
+}
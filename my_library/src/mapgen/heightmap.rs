@@ -0,0 +1,91 @@
+use rand::Rng;
+use crate::{Fractal, Noise};
+use super::{Grid, Tile};
+
+/// Side-view terrain: a cross-section through a noise height map.
+/// Every column is solid from the bottom of the map up to its ground
+/// height, giving rolling hills for platformers and landers.
+///
+/// ## Example
+///
+/// ```
+/// use my_library::{RandomNumberGenerator, mapgen::HeightMap};
+/// let mut rng = RandomNumberGenerator::seeded(1);
+/// let settings = HeightMap::default();
+/// let heights = settings.heights(&mut rng);
+/// assert_eq!(heights.len(), settings.width as usize);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeightMap {
+  /// Width of the map, in cells.
+  pub width: i32,
+  /// Height of the map, in cells.
+  pub height: i32,
+  /// Average ground level, as a fraction (`0.0..=1.0`) of the height.
+  pub ground: f32,
+  /// How far hills rise and valleys sink, as a fraction of the height.
+  pub amplitude: f32,
+  /// The noise settings. `frequency` is per cell, so small values
+  /// (around 0.05) give broad hills.
+  pub fractal: Fractal,
+}
+
+impl Default for HeightMap {
+  fn default() -> Self {
+    Self {
+      width: 80,
+      height: 50,
+      ground: 0.3,
+      amplitude: 0.2,
+      fractal: Fractal { frequency: 0.05, ..Default::default() },
+    }
+  }
+}
+
+impl HeightMap {
+  /// Generates the ground height of every column, in cells: each
+  /// column is solid up to (but not including) its height. Heights are
+  /// clamped to the map.
+  pub fn heights<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<i32> {
+    let noise = Noise::new(rng);
+    (0..self.width)
+      .map(|x| {
+        let level = self.ground + noise.fbm_1d(x as f32, &self.fractal) * self.amplitude;
+        ((level * self.height as f32).round() as i32).clamp(0, self.height)
+      })
+      .collect()
+  }
+
+  /// Generates the terrain: walls below the ground, open space above.
+  pub fn generate<R: Rng + ?Sized>(&self, rng: &mut R) -> Grid<Tile> {
+    let heights = self.heights(rng);
+    Grid::from_fn(self.width, self.height, |pos| {
+      if pos.y < heights[pos.x as usize] { Tile::Wall } else { Tile::Floor }
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::RandomNumberGenerator;
+  use bevy::math::IVec2;
+
+  #[test]
+  fn test_cross_section() {
+    let settings = HeightMap::default();
+    let map = settings.generate(&mut RandomNumberGenerator::seeded(1));
+    let heights = settings.heights(&mut RandomNumberGenerator::seeded(1));
+    for x in 0..settings.width {
+      let h = heights[x as usize];
+      assert!((0..=settings.height).contains(&h));
+      // Solid below the surface, open above it
+      for y in 0..settings.height {
+        let expected = if y < h { Tile::Wall } else { Tile::Floor };
+        assert_eq!(map[IVec2::new(x, y)], expected);
+      }
+    }
+    // Broad hills: neighbouring columns are close
+    assert!(heights.windows(2).all(|w| (w[0] - w[1]).abs() <= 3));
+  }
+}
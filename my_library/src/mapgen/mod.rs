@@ -0,0 +1,39 @@
+//! Procedural map generators. Each one is a settings struct (with
+//! sensible defaults) whose `generate` method builds a [`Grid`] of
+//! [`Tile`]s from a `RandomNumberGenerator`. Grids can be combined,
+//! then turned into sprites with [`Grid::to_world`].
+//!
+//! ## Example
+//!
+//! ```
+//! use my_library::{RandomNumberGenerator, mapgen::*};
+//! let mut rng = RandomNumberGenerator::seeded(1);
+//! let caves = CellularAutomata { width: 64, height: 48, ..Default::default() }
+//!   .generate(&mut rng);
+//! let ground = HeightMap { width: 64, height: 48, ..Default::default() }
+//!   .generate(&mut rng);
+//! // A cave system above rolling hills
+//! let map = caves.map(|pos, tile| {
+//!   if ground[pos] == Tile::Wall { Tile::Wall } else { *tile }
+//! });
+//! // Where to spawn_image! each rock sprite
+//! let rocks: Vec<_> = map.iter()
+//!   .filter(|(_, tile)| **tile == Tile::Wall)
+//!   .map(|(pos, _)| map.to_world(pos, 16.0))
+//!   .collect();
+//! ```
+
+mod grid;
+pub use grid::*;
+
+mod cellular;
+pub use cellular::*;
+
+mod drunkard;
+pub use drunkard::*;
+
+mod bsp;
+pub use bsp::*;
+
+mod heightmap;
+pub use heightmap::*;
@@ -0,0 +1,235 @@
+use bevy::utils::{HashMap, HashSet};
+use rand::Rng;
+
+/// How a [`Markov`] generator splits its training text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarkovUnit {
+  /// Letter by letter, for inventing names.
+  Letters,
+  /// Word by word, for inventing sentences.
+  Words,
+}
+
+// `None` marks the start (as padding) and end of an entry
+type Token = Option<String>;
+
+/// `Markov` invents names (or text) that sound like its training data,
+/// using a Markov chain: it learns which letter tends to follow each
+/// run of `order` letters, then strings together new names one letter
+/// at a time.
+///
+/// Higher orders copy the training data more closely; order 2 or 3
+/// usually works best for names. Generated names that appear in the
+/// training data are rejected, so every result is new.
+///
+/// Word lists can be loaded from `assets/` with
+/// [`AssetManager::add_word_list`](crate::AssetManager::add_word_list).
+///
+/// ## Example
+///
+/// ```
+/// use my_library::{Markov, RandomNumberGenerator};
+/// let mut rng = RandomNumberGenerator::seeded(1);
+/// let dragons = Markov::letters(2)
+///   .with_length(4, 9)
+///   .trained(["Smaug", "Glaurung", "Ancalagon", "Fafnir", "Vermithrax",
+///     "Norbert", "Saphira", "Drogon", "Viserion", "Rhaegal", "Toothless"]);
+/// if let Some(name) = dragons.generate(&mut rng) {
+///   assert!((4..=9).contains(&name.chars().count()));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Markov {
+  unit: MarkovUnit,
+  order: usize,
+  min_length: usize,
+  max_length: usize,
+  chain: HashMap<Vec<Token>, Vec<(Token, u32)>>,
+  training: HashSet<String>,
+}
+
+impl Markov {
+  const ATTEMPTS: usize = 100;
+
+  /// Creates an untrained name generator, working letter by letter.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `order` is zero.
+  pub fn letters(order: usize) -> Self {
+    Self::new(MarkovUnit::Letters, order)
+  }
+
+  /// Creates an untrained text generator, working word by word.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `order` is zero.
+  pub fn words(order: usize) -> Self {
+    Self::new(MarkovUnit::Words, order)
+  }
+
+  fn new(unit: MarkovUnit, order: usize) -> Self {
+    assert!(order > 0, "Markov: order must be at least 1");
+    Self {
+      unit,
+      order,
+      min_length: 1,
+      max_length: usize::MAX,
+      chain: HashMap::new(),
+      training: HashSet::new(),
+    }
+  }
+
+  /// Only generate results between `min` and `max` letters (or words)
+  /// long, inclusive.
+  pub fn with_length(mut self, min: usize, max: usize) -> Self {
+    self.min_length = min;
+    self.max_length = max;
+    self
+  }
+
+  /// Learns from one entry: a name, or a sentence.
+  pub fn train(&mut self, entry: &str) {
+    let tokens: Vec<String> = match self.unit {
+      MarkovUnit::Letters => entry.trim().chars().map(String::from).collect(),
+      MarkovUnit::Words => entry.split_whitespace().map(String::from).collect(),
+    };
+    if tokens.is_empty() {
+      return;
+    }
+    let mut sequence: Vec<Token> = vec![None; self.order];
+    sequence.extend(tokens.into_iter().map(Some));
+    sequence.push(None);
+    for window in sequence.windows(self.order + 1) {
+      let (key, next) = window.split_at(self.order);
+      let choices = self.chain.entry(key.to_vec()).or_default();
+      match choices.iter_mut().find(|(token, _)| *token == next[0]) {
+        Some((_, count)) => *count += 1,
+        None => choices.push((next[0].clone(), 1)),
+      }
+    }
+    self.training.insert(self.join(&sequence[self.order..sequence.len() - 1]));
+  }
+
+  /// Learns from every entry, and returns the trained generator.
+  pub fn trained<S: AsRef<str>>(mut self, entries: impl IntoIterator<Item = S>) -> Self {
+    for entry in entries {
+      self.train(entry.as_ref());
+    }
+    self
+  }
+
+  /// Has the generator learned anything yet?
+  pub fn is_trained(&self) -> bool {
+    !self.chain.is_empty()
+  }
+
+  fn join(&self, tokens: &[Token]) -> String {
+    let tokens = tokens.iter().flatten().map(String::as_str);
+    match self.unit {
+      MarkovUnit::Letters => tokens.collect(),
+      MarkovUnit::Words => tokens.collect::<Vec<_>>().join(" "),
+    }
+  }
+
+  // Walks the chain once. Returns `None` if it ran past `max_length`.
+  fn walk<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<(String, usize)> {
+    let mut sequence: Vec<Token> = vec![None; self.order];
+    loop {
+      let key = &sequence[sequence.len() - self.order..];
+      let choices = self.chain.get(key)?;
+      let total: u32 = choices.iter().map(|(_, count)| count).sum();
+      let mut roll = rng.gen_range(0..total);
+      let (next, _) = choices
+        .iter()
+        .find(|(_, count)| {
+          let found = roll < *count;
+          roll = roll.saturating_sub(*count);
+          found
+        })?;
+      match next {
+        Some(token) => sequence.push(Some(token.clone())),
+        None => break,
+      }
+      if sequence.len() - self.order > self.max_length {
+        return None;
+      }
+    }
+    let length = sequence.len() - self.order;
+    Some((self.join(&sequence), length))
+  }
+
+  /// Generates a new name (or sentence). Returns `None` if the
+  /// generator isn't trained, or no acceptable result turned up after
+  /// a reasonable number of tries---usually because the length limits
+  /// don't suit the training data.
+  pub fn generate<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<String> {
+    (0..Self::ATTEMPTS)
+      .filter_map(|_| self.walk(rng))
+      .find(|(text, length)| {
+        (self.min_length..=self.max_length).contains(length) && !self.training.contains(text)
+      })
+      .map(|(text, _)| text)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::RandomNumberGenerator;
+
+  const NAMES: [&str; 12] = [
+    "Gale", "Jezero", "Olympus", "Hellas", "Elysium", "Tharsis",
+    "Utopia", "Argyre", "Gusev", "Meridiani", "Cydonia", "Arcadia",
+  ];
+
+  #[test]
+  fn test_names() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let markov = Markov::letters(2).with_length(4, 8).trained(NAMES);
+    let names: Vec<String> = (0..50).filter_map(|_| markov.generate(&mut rng)).collect();
+    assert!(names.len() > 40);
+    for name in &names {
+      assert!((4..=8).contains(&name.chars().count()), "{name}");
+      assert!(!NAMES.contains(&name.as_str()));
+      // Every letter pair must have been seen in training
+      let padded: Vec<char> = name.chars().collect();
+      for pair in padded.windows(2) {
+        let pair: String = pair.iter().collect();
+        assert!(NAMES.iter().any(|n| n.contains(&pair)), "{pair} in {name}");
+      }
+    }
+  }
+
+  #[test]
+  fn test_reproducibility() {
+    let markov = Markov::letters(3).trained(NAMES);
+    let mut rng = (RandomNumberGenerator::seeded(7), RandomNumberGenerator::seeded(7));
+    let a: Vec<_> = (0..10).map(|_| markov.generate(&mut rng.0)).collect();
+    let b: Vec<_> = (0..10).map(|_| markov.generate(&mut rng.1)).collect();
+    assert_eq!(a, b);
+  }
+
+  #[test]
+  fn test_words() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let markov = Markov::words(1).trained([
+      "the dragon flies over the wall",
+      "the ship lands on the red planet",
+      "a dragon lands on the wall",
+    ]);
+    let text = markov.generate(&mut rng).unwrap();
+    let first = text.split(' ').next().unwrap();
+    assert!(first == "the" || first == "a");
+  }
+
+  #[test]
+  fn test_untrained_and_impossible() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    assert!(!Markov::letters(2).is_trained());
+    assert_eq!(Markov::letters(2).generate(&mut rng), None);
+    let too_long = Markov::letters(2).with_length(50, 60).trained(NAMES);
+    assert_eq!(too_long.generate(&mut rng), None);
+  }
+}
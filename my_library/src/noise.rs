@@ -0,0 +1,286 @@
+use rand::Rng;
+use crate::RandomNumberGenerator;
+
+/// Settings for fractal Brownian motion (fBm): several octaves of noise
+/// layered at increasing frequency and decreasing amplitude.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fractal {
+  /// How many layers of noise to combine. More octaves add finer detail.
+  pub octaves: u32,
+  /// Frequency of the first octave. Lower values give broader features.
+  pub frequency: f32,
+  /// Frequency multiplier between octaves (usually 2).
+  pub lacunarity: f32,
+  /// Amplitude multiplier between octaves (usually 0.5). Lower values
+  /// give smoother results.
+  pub gain: f32,
+}
+
+impl Default for Fractal {
+  fn default() -> Self {
+    Self {
+      octaves: 4,
+      frequency: 1.0,
+      lacunarity: 2.0,
+      gain: 0.5,
+    }
+  }
+}
+
+/// `Noise` generates coherent noise: random values that change smoothly
+/// as you move through space, rather than jumping around like
+/// [`RandomNumberGenerator::range`]. It's useful for terrain, clouds,
+/// or anything that should "drift" rather than flicker.
+///
+/// All functions return values in (roughly) `-1.0..=1.0`, and always
+/// return the same value for the same coordinates and seed.
+///
+/// ## Example
+///
+/// ```
+/// use my_library::{Fractal, Noise, RandomNumberGenerator};
+/// let mut rng = RandomNumberGenerator::seeded(1);
+/// let noise = Noise::new(&mut rng);
+/// let fractal = Fractal { frequency: 0.01, ..Default::default() };
+/// let heights: Vec<f32> = (0..100)
+///   .map(|x| noise.fbm_1d(x as f32, &fractal) * 50.0)
+///   .collect();
+/// assert!(heights.iter().all(|h| h.abs() <= 50.0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Noise {
+  perm: Vec<u8>,
+}
+
+impl Noise {
+  /// Creates a noise generator, seeded from `rng`.
+  pub fn new<R: Rng + ?Sized>(rng: &mut R) -> Self {
+    let mut perm: Vec<u8> = (0..=255).collect();
+    for i in (1..perm.len()).rev() {
+      let j = rng.gen_range(0..=i);
+      perm.swap(i, j);
+    }
+    perm.extend_from_within(..);
+    Self { perm }
+  }
+
+  /// Creates a noise generator from a seed. The same seed always gives
+  /// the same noise.
+  pub fn seeded(seed: u64) -> Self {
+    Self::new(&mut RandomNumberGenerator::seeded(seed))
+  }
+
+  fn hash(&self, i: i32) -> usize {
+    self.perm[(i & 255) as usize] as usize
+  }
+
+  fn hash2(&self, x: i32, y: i32) -> usize {
+    self.perm[self.hash(x) + (y & 255) as usize] as usize
+  }
+
+  fn hash3(&self, x: i32, y: i32, z: i32) -> usize {
+    self.perm[self.hash2(x, y) + (z & 255) as usize] as usize
+  }
+
+  /// One dimensional gradient (Perlin) noise.
+  pub fn perlin_1d(&self, x: f32) -> f32 {
+    let xi = x.floor() as i32;
+    let xf = x - x.floor();
+    let g0 = grad1(self.hash(xi), xf);
+    let g1 = grad1(self.hash(xi + 1), xf - 1.0);
+    // The largest possible 1D result is 0.5, so scale to -1..1
+    lerp(g0, g1, fade(xf)) * 2.0
+  }
+
+  /// Two dimensional gradient (Perlin) noise.
+  pub fn perlin_2d(&self, x: f32, y: f32) -> f32 {
+    let (xi, yi) = (x.floor() as i32, y.floor() as i32);
+    let (xf, yf) = (x - x.floor(), y - y.floor());
+    let (u, v) = (fade(xf), fade(yf));
+    let n00 = grad2(self.hash2(xi, yi), xf, yf);
+    let n10 = grad2(self.hash2(xi + 1, yi), xf - 1.0, yf);
+    let n01 = grad2(self.hash2(xi, yi + 1), xf, yf - 1.0);
+    let n11 = grad2(self.hash2(xi + 1, yi + 1), xf - 1.0, yf - 1.0);
+    let result = lerp(lerp(n00, n10, u), lerp(n01, n11, u), v);
+    (result * std::f32::consts::SQRT_2).clamp(-1.0, 1.0)
+  }
+
+  /// Three dimensional gradient (Perlin) noise.
+  pub fn perlin_3d(&self, x: f32, y: f32, z: f32) -> f32 {
+    let (xi, yi, zi) = (x.floor() as i32, y.floor() as i32, z.floor() as i32);
+    let (xf, yf, zf) = (x - x.floor(), y - y.floor(), z - z.floor());
+    let (u, v, w) = (fade(xf), fade(yf), fade(zf));
+    let corner = |dx: i32, dy: i32, dz: i32| {
+      grad3(
+        self.hash3(xi + dx, yi + dy, zi + dz),
+        xf - dx as f32,
+        yf - dy as f32,
+        zf - dz as f32,
+      )
+    };
+    let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), u);
+    let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), u);
+    let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), u);
+    let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), u);
+    lerp(lerp(x00, x10, v), lerp(x01, x11, v), w).clamp(-1.0, 1.0)
+  }
+
+  /// Two dimensional simplex noise. It has fewer directional artifacts
+  /// than [`Noise::perlin_2d`], and is a little cheaper.
+  pub fn simplex_2d(&self, x: f32, y: f32) -> f32 {
+    const F2: f32 = 0.366_025_42; // (sqrt(3) - 1) / 2
+    const G2: f32 = 0.211_324_87; // (3 - sqrt(3)) / 6
+    let s = (x + y) * F2;
+    let (i, j) = ((x + s).floor(), (y + s).floor());
+    let t = (i + j) * G2;
+    let (x0, y0) = (x - (i - t), y - (j - t));
+    let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+    let (x1, y1) = (x0 - i1 as f32 + G2, y0 - j1 as f32 + G2);
+    let (x2, y2) = (x0 - 1.0 + 2.0 * G2, y0 - 1.0 + 2.0 * G2);
+    let (i, j) = (i as i32, j as i32);
+    let corner = |hash: usize, x: f32, y: f32| {
+      let t = 0.5 - x * x - y * y;
+      if t < 0.0 { 0.0 } else { t.powi(4) * grad2(hash, x, y) }
+    };
+    let n0 = corner(self.hash2(i, j), x0, y0);
+    let n1 = corner(self.hash2(i + i1, j + j1), x1, y1);
+    let n2 = corner(self.hash2(i + 1, j + 1), x2, y2);
+    (70.0 * (n0 + n1 + n2)).clamp(-1.0, 1.0)
+  }
+
+  /// Two dimensional value noise: random values at each integer point,
+  /// smoothly blended. Blockier than gradient noise, but cheap.
+  pub fn value_2d(&self, x: f32, y: f32) -> f32 {
+    let (xi, yi) = (x.floor() as i32, y.floor() as i32);
+    let (u, v) = (fade(x - x.floor()), fade(y - y.floor()));
+    let value = |dx: i32, dy: i32| self.hash2(xi + dx, yi + dy) as f32 / 127.5 - 1.0;
+    lerp(lerp(value(0, 0), value(1, 0), u), lerp(value(0, 1), value(1, 1), u), v)
+  }
+
+  /// One dimensional fractal Brownian motion, built from [`Noise::perlin_1d`].
+  pub fn fbm_1d(&self, x: f32, fractal: &Fractal) -> f32 {
+    fbm(fractal, |f| self.perlin_1d(x * f))
+  }
+
+  /// Two dimensional fractal Brownian motion, built from [`Noise::perlin_2d`].
+  pub fn fbm_2d(&self, x: f32, y: f32, fractal: &Fractal) -> f32 {
+    fbm(fractal, |f| self.perlin_2d(x * f, y * f))
+  }
+
+  /// Three dimensional fractal Brownian motion, built from [`Noise::perlin_3d`].
+  pub fn fbm_3d(&self, x: f32, y: f32, z: f32, fractal: &Fractal) -> f32 {
+    fbm(fractal, |f| self.perlin_3d(x * f, y * f, z * f))
+  }
+
+  /// Domain-warped fBm: the sample position is first pushed around by
+  /// two other fBm fields, which gives swirling, eroded-looking shapes.
+  ///
+  /// # Arguments
+  ///
+  /// * `x`, `y` - the position to sample.
+  /// * `fractal` - the fBm settings, used for both the warp and the result.
+  /// * `strength` - how far (in input units) positions are displaced.
+  pub fn warp_2d(&self, x: f32, y: f32, fractal: &Fractal, strength: f32) -> f32 {
+    // Offsets decorrelate the two warp fields from each other
+    let wx = self.fbm_2d(x + 17.3, y + 41.9, fractal);
+    let wy = self.fbm_2d(x - 29.1, y + 7.7, fractal);
+    self.fbm_2d(x + wx * strength, y + wy * strength, fractal)
+  }
+}
+
+fn fbm(fractal: &Fractal, mut sample: impl FnMut(f32) -> f32) -> f32 {
+  let mut frequency = fractal.frequency;
+  let mut amplitude = 1.0;
+  let mut total = 0.0;
+  let mut max = 0.0;
+  for _ in 0..fractal.octaves {
+    total += sample(frequency) * amplitude;
+    max += amplitude;
+    frequency *= fractal.lacunarity;
+    amplitude *= fractal.gain;
+  }
+  if max > 0.0 { total / max } else { 0.0 }
+}
+
+fn fade(t: f32) -> f32 {
+  t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+  a + t * (b - a)
+}
+
+fn grad1(hash: usize, x: f32) -> f32 {
+  // Gradients in -1..1, skipping zero
+  let g = (hash & 7) as f32 / 8.0 + 0.125;
+  if hash & 8 == 0 { g * x } else { -g * x }
+}
+
+fn grad2(hash: usize, x: f32, y: f32) -> f32 {
+  match hash & 7 {
+    0 => x + y,
+    1 => -x + y,
+    2 => x - y,
+    3 => -x - y,
+    4 => x,
+    5 => -x,
+    6 => y,
+    _ => -y,
+  }
+}
+
+fn grad3(hash: usize, x: f32, y: f32, z: f32) -> f32 {
+  // Ken Perlin's 12 cube-edge gradients (with 4 repeats)
+  let h = hash & 15;
+  let u = if h < 8 { x } else { y };
+  let v = if h < 4 { y } else if h == 12 || h == 14 { x } else { z };
+  (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_noise_range() {
+    let noise = Noise::seeded(1);
+    let fractal = Fractal::default();
+    for i in 0..1000 {
+      let x = i as f32 * 0.173 - 50.0;
+      let y = i as f32 * 0.311 + 7.0;
+      for n in [
+        noise.perlin_1d(x),
+        noise.perlin_2d(x, y),
+        noise.perlin_3d(x, y, x - y),
+        noise.simplex_2d(x, y),
+        noise.value_2d(x, y),
+        noise.fbm_1d(x, &fractal),
+        noise.fbm_3d(x, y, 0.5, &fractal),
+        noise.warp_2d(x, y, &fractal, 4.0),
+      ] {
+        assert!((-1.0..=1.0).contains(&n), "{n} out of range");
+      }
+    }
+  }
+
+  #[test]
+  fn test_noise_is_smooth() {
+    let noise = Noise::seeded(1);
+    for i in 0..1000 {
+      let x = i as f32 * 0.05;
+      assert!((noise.perlin_1d(x) - noise.perlin_1d(x + 0.001)).abs() < 0.01);
+      assert!((noise.perlin_2d(x, 1.5) - noise.perlin_2d(x + 0.001, 1.5)).abs() < 0.01);
+    }
+  }
+
+  #[test]
+  fn test_noise_reproducibility() {
+    let (a, b, c) = (Noise::seeded(1), Noise::seeded(1), Noise::seeded(2));
+    let different = (0..100).filter(|i| {
+      let x = *i as f32 * 0.37;
+      assert_eq!(a.perlin_2d(x, -x), b.perlin_2d(x, -x));
+      a.perlin_2d(x, -x) != c.perlin_2d(x, -x)
+    }).count();
+    assert!(different > 50);
+  }
+}
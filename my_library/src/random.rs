@@ -1,21 +1,25 @@
-use rand::{Rng, SeedableRng, distributions::uniform::{SampleRange, SampleUniform}};
-
-#[cfg(all(not(feature = "pcg"), not(feature = "xorshift")))]
-type RngCore = rand::prelude::StdRng;
-
-#[cfg(feature = "pcg")]
-type RngCore = rand_pcg::Pcg64Mcg;
-
-#[cfg(feature = "xorshift")]
-type RngCore = rand_xorshift::XorShiftRng;
+use rand::{
+  Rng, RngCore,
+  distributions::uniform::{SampleRange, SampleUniform},
+  seq::{IteratorRandom, SliceRandom},
+};
+use serde::{Deserialize, Serialize};
+use bevy::math::{Rect, Vec2, Vec3};
+use std::hash::Hash;
+use crate::{
+  Algorithm, DiceExpression, DiceRoll, RandomState,
+  algorithm::Backend, distributions, random_streams::stream_seed, sampling,
+};
 
 /// `RandomNumberGenerator` holds random number generation state, and offers 
 /// random number generation services to your program.
 /// 
 /// `RandomNumberGenerator` defaults to using the 
 /// [PCG](https://crates.io/crates/rand_pcg)(1)
-/// algorithm. You can specify `xorshift` as a feature flag to use it 
-/// instead.
+/// algorithm. To default to XorShift instead, enable the `xorshift`
+/// feature with `default-features = false`: `pcg` is a default feature,
+/// and wins when both are enabled. Any algorithm can also be chosen at
+/// runtime; see [`Algorithm`] and [`RandomNumberGenerator::with_algorithm`].
 /// 
 /// By default, `RandomNumberGenerator` requires mutability---it 
 /// is shared in Bevy with `ResMut<RandomNumberGenerator>`. If 
@@ -23,6 +27,15 @@ type RngCore = rand_xorshift::XorShiftRng;
 /// `Res<RandomNumberGenerator>` instead), specify the `locking`
 /// feature flag.
 /// 
+/// The generator's full state can be saved and restored with
+/// [`RandomNumberGenerator::snapshot`] and
+/// [`RandomNumberGenerator::restore`], or by serializing the generator
+/// itself with `serde`.
+///
+/// It implements `rand::RngCore`, so it can drive anything generic
+/// over [`Rng`](crate::rand::Rng), such as [`WeightedTable`](crate::WeightedTable)
+/// or the [`mapgen`](crate::mapgen) generators.
+/// 
 /// ## Example
 /// 
 /// (2)
@@ -32,9 +45,10 @@ type RngCore = rand_xorshift::XorShiftRng;
 /// let random_number = my_rng.range(1..10);
 /// println!("{random_number}");
 /// ```
-#[derive(bevy::prelude::Resource)]
+#[derive(bevy::prelude::Resource, Clone, Serialize, Deserialize)]
+#[serde(from = "RandomState", into = "RandomState")]
 pub struct RandomNumberGenerator {
-  rng: RngCore,
+  rng: Backend,
 }
 
 impl Default for RandomNumberGenerator {
@@ -48,7 +62,7 @@ impl RandomNumberGenerator {
   /// selected starting seed.
   pub fn new() -> Self {
     Self {
-      rng: RngCore::from_entropy(),
+      rng: Backend::from_entropy(Algorithm::default()),
     }
   }
 
@@ -70,10 +84,86 @@ impl RandomNumberGenerator {
   /// ```
   pub fn seeded(seed: u64) -> Self {
     Self {
-      rng: RngCore::seed_from_u64(seed),
+      rng: Backend::seed_from_u64(Algorithm::default(), seed),
+    }
+  }
+
+  /// Creates a `RandomNumberGenerator` using a specific [`Algorithm`],
+  /// regardless of which one the feature flags made the default.
+  ///
+  /// # Arguments
+  ///
+  /// * `algorithm` - the generation algorithm to use.
+  /// * `seed` - the random seed to use.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use my_library::{Algorithm, RandomNumberGenerator};
+  /// let mut rng = RandomNumberGenerator::with_algorithm(Algorithm::XorShift, 1);
+  /// assert_eq!(rng.algorithm(), Algorithm::XorShift);
+  /// let n = rng.range(1..10);
+  /// ```
+  pub fn with_algorithm(algorithm: Algorithm, seed: u64) -> Self {
+    Self {
+      rng: Backend::seed_from_u64(algorithm, seed),
+    }
+  }
+
+  /// Creates a `RandomNumberGenerator` using a specific [`Algorithm`],
+  /// with a randomly selected starting seed.
+  pub fn new_with_algorithm(algorithm: Algorithm) -> Self {
+    Self {
+      rng: Backend::from_entropy(algorithm),
     }
   }
 
+  /// The algorithm this generator uses.
+  pub fn algorithm(&self) -> Algorithm {
+    self.rng.algorithm()
+  }
+
+  /// Creates a `RandomNumberGenerator` that continues from a saved
+  /// [`RandomState`].
+  pub fn from_state(state: RandomState) -> Self {
+    Self { rng: state.0 }
+  }
+
+  /// Captures the generator's current state. Restoring it later
+  /// replays the exact same sequence of numbers from this point.
+  pub fn snapshot(&self) -> RandomState {
+    RandomState(self.rng.clone())
+  }
+
+  /// Rewinds (or fast-forwards) the generator to a saved state.
+  pub fn restore(&mut self, state: &RandomState) {
+    self.rng = state.0.clone();
+  }
+
+  /// Creates an independent generator for stream `stream`, derived
+  /// from this generator's current state. The parent is not advanced,
+  /// so forking doesn't disturb its sequence, and forking the same
+  /// stream from the same state always gives the same generator.
+  ///
+  /// # Arguments
+  ///
+  /// * `stream` - any hashable stream identifier, such as a label
+  ///   or an `Entity`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use my_library::RandomNumberGenerator;
+  /// let rng = RandomNumberGenerator::seeded(1);
+  /// let mut walls = rng.fork("walls");
+  /// let mut walls_again = rng.fork("walls");
+  /// assert_eq!(walls.next::<u32>(), walls_again.next::<u32>());
+  /// ```
+  pub fn fork<K: Hash + ?Sized>(&self, stream: &K) -> Self {
+    let base: u64 = self.rng.clone().gen();
+    Self::with_algorithm(self.algorithm(), stream_seed(base, stream))
+  }
+
   /// Generates a new random number of the requested type.
   pub fn next<T>(&mut self) -> T
   where rand::distributions::Standard: rand::prelude::Distribution<T>
@@ -102,6 +192,269 @@ impl RandomNumberGenerator {
   {
     self.rng.gen_range(range)
   }
+
+  /// Shuffles a slice in place (Fisher-Yates), so every ordering is
+  /// equally likely.
+  pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+    slice.shuffle(&mut self.rng);
+  }
+
+  /// Picks a random element of a slice, or `None` if it is empty.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use my_library::RandomNumberGenerator;
+  /// let mut rng = RandomNumberGenerator::new();
+  /// let name = rng.choose(&["Smaug", "Puff", "Norbert"]).unwrap();
+  /// ```
+  pub fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
+    slice.choose(&mut self.rng)
+  }
+
+  /// Picks `amount` distinct elements of a slice (or all of them, if
+  /// there are fewer), in random order.
+  pub fn choose_multiple<'a, T>(&mut self, slice: &'a [T], amount: usize) -> Vec<&'a T> {
+    slice.choose_multiple(&mut self.rng, amount).collect()
+  }
+
+  /// Picks `amount` distinct items from an iterator of unknown length
+  /// using reservoir sampling, consuming the iterator once without
+  /// collecting it. The order of the result is not random; shuffle it
+  /// if that matters.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use my_library::RandomNumberGenerator;
+  /// let mut rng = RandomNumberGenerator::new();
+  /// let evens = rng.sample((0..1000).filter(|n| n % 2 == 0), 3);
+  /// assert_eq!(evens.len(), 3);
+  /// ```
+  pub fn sample<I: IntoIterator>(&mut self, iter: I, amount: usize) -> Vec<I::Item> {
+    iter.into_iter().choose_multiple(&mut self.rng, amount)
+  }
+
+  /// Samples a normal (Gaussian) distribution: values cluster around
+  /// `mean`, with about 68% falling within one `std_dev` of it.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `std_dev` is not finite.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use my_library::RandomNumberGenerator;
+  /// let mut rng = RandomNumberGenerator::new();
+  /// let speed = rng.normal(5.0, 0.5);
+  /// ```
+  pub fn normal(&mut self, mean: f32, std_dev: f32) -> f32 {
+    self.rng.sample(distributions::normal(mean, std_dev))
+  }
+
+  /// Samples an exponential distribution: the time between events that
+  /// happen `rate` times per unit of time, on average. Handy for
+  /// natural-looking spawn timers.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `rate` is negative.
+  pub fn exponential(&mut self, rate: f32) -> f32 {
+    self.rng.sample(distributions::exponential(rate))
+  }
+
+  /// Samples a Poisson distribution: how many events happen in a unit
+  /// of time, when `mean` happen on average.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `mean` is not positive and finite.
+  pub fn poisson(&mut self, mean: f32) -> u64 {
+    self.rng.sample(distributions::poisson(mean)) as u64
+  }
+
+  /// Samples a binomial distribution: how many of `trials` attempts
+  /// succeed, when each succeeds with `probability`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `probability` is outside `0.0..=1.0`.
+  pub fn binomial(&mut self, trials: u64, probability: f64) -> u64 {
+    self.rng.sample(distributions::binomial(trials, probability))
+  }
+
+  /// Samples a triangular distribution between `min` and `max`, most
+  /// likely to return values near `mode`. A cheap, bounded alternative
+  /// to [`RandomNumberGenerator::normal`].
+  ///
+  /// # Panics
+  ///
+  /// Panics unless `min <= mode <= max`.
+  pub fn triangular(&mut self, min: f32, max: f32, mode: f32) -> f32 {
+    self.rng.sample(distributions::triangular(min, max, mode))
+  }
+
+  /// Samples a beta distribution, returning a value in `0.0..=1.0`.
+  /// `alpha > beta` skews results towards 1, `alpha < beta` towards 0,
+  /// and large equal values cluster them around 0.5.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `alpha` or `beta` is not positive.
+  pub fn beta(&mut self, alpha: f32, beta: f32) -> f32 {
+    self.rng.sample(distributions::beta(alpha, beta))
+  }
+
+  /// Picks a random direction: a point on the unit circle.
+  pub fn unit_vec2(&mut self) -> Vec2 {
+    sampling::unit_vec2(&mut self.rng)
+  }
+
+  /// Picks a random direction in 3D: a point on the unit sphere.
+  pub fn unit_vec3(&mut self) -> Vec3 {
+    sampling::unit_vec3(&mut self.rng)
+  }
+
+  /// Picks a point inside a circle, uniformly by area (so points don't
+  /// bunch up in the middle).
+  ///
+  /// # Panics
+  ///
+  /// Panics if `radius` is negative.
+  pub fn point_in_circle(&mut self, center: Vec2, radius: f32) -> Vec2 {
+    sampling::in_annulus(&mut self.rng, center, 0.0, radius)
+  }
+
+  /// Picks a point inside a ring between `inner` and `outer` radius,
+  /// uniformly by area. Handy for spawning things near---but not
+  /// on top of---the player.
+  ///
+  /// # Panics
+  ///
+  /// Panics unless `0 <= inner <= outer`.
+  pub fn point_in_annulus(&mut self, center: Vec2, inner: f32, outer: f32) -> Vec2 {
+    sampling::in_annulus(&mut self.rng, center, inner, outer)
+  }
+
+  /// Picks a point inside a rectangle.
+  pub fn point_in_rect(&mut self, rect: Rect) -> Vec2 {
+    sampling::in_rect(&mut self.rng, rect)
+  }
+
+  /// Picks a point inside a simple (not self-intersecting) polygon,
+  /// convex or not, uniformly by area.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the polygon has fewer than 3 vertices or no area.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use bevy::math::Vec2;
+  /// use my_library::RandomNumberGenerator;
+  /// let mut rng = RandomNumberGenerator::new();
+  /// let triangle = [Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(0.0, 10.0)];
+  /// let p = rng.point_in_polygon(&triangle);
+  /// assert!(p.x >= 0.0 && p.y >= 0.0 && p.x + p.y <= 10.0);
+  /// ```
+  pub fn point_in_polygon(&mut self, polygon: &[Vec2]) -> Vec2 {
+    sampling::in_polygon(&mut self.rng, polygon)
+  }
+
+  /// Fills `area` with points that are at least `min_distance` apart,
+  /// but otherwise evenly and randomly spread (Bridson's Poisson-disc
+  /// sampling). Use it to place things that mustn't overlap, such as
+  /// trees or spawn points.
+  ///
+  /// # Panics
+  ///
+  /// Panics unless `min_distance` is positive.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use bevy::math::{Rect, Vec2};
+  /// use my_library::RandomNumberGenerator;
+  /// let mut rng = RandomNumberGenerator::new();
+  /// let trees = rng.poisson_disc(Rect::new(0.0, 0.0, 100.0, 100.0), 10.0);
+  /// assert!(trees.iter().all(|a| trees.iter().all(|b| a == b || a.distance(*b) >= 10.0)));
+  /// ```
+  pub fn poisson_disc(&mut self, area: Rect, min_distance: f32) -> Vec<Vec2> {
+    sampling::poisson_disc(&mut self.rng, area, min_distance, &[], usize::MAX)
+  }
+
+  /// Like [`RandomNumberGenerator::poisson_disc`], but keeps clear of
+  /// `existing` points, filling only the gaps between them. Only the
+  /// new points are returned.
+  pub fn poisson_disc_around(&mut self, area: Rect, min_distance: f32, existing: &[Vec2]) -> Vec<Vec2> {
+    sampling::poisson_disc(&mut self.rng, area, min_distance, existing, usize::MAX)
+  }
+
+  /// Like [`RandomNumberGenerator::poisson_disc_around`], but stops
+  /// after `count` new points, which is much faster than filling the
+  /// whole area to use a few of them.
+  pub fn poisson_disc_up_to(&mut self, area: Rect, min_distance: f32, existing: &[Vec2], count: usize) -> Vec<Vec2> {
+    sampling::poisson_disc(&mut self.rng, area, min_distance, existing, count)
+  }
+
+  /// Rolls dice described in dice notation, such as `"3d6+2"`,
+  /// `"2d20kh1"` or `"4d6!"`. See [`DiceExpression`] for the full
+  /// notation.
+  ///
+  /// # Arguments
+  ///
+  /// * `expression` - the dice notation to parse and roll.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use my_library::RandomNumberGenerator;
+  /// let mut rng = RandomNumberGenerator::new();
+  /// let roll = rng.roll_dice("3d6+2").unwrap();
+  /// assert!(roll.total >= 5 && roll.total <= 20);
+  /// assert_eq!(roll.dice().count(), 3);
+  /// ```
+  pub fn roll_dice(&mut self, expression: &str) -> anyhow::Result<DiceRoll> {
+    let dice = DiceExpression::parse(expression)?;
+    Ok(self.roll(&dice))
+  }
+
+  /// Rolls a pre-parsed [`DiceExpression`].
+  pub fn roll(&mut self, dice: &DiceExpression) -> DiceRoll {
+    dice.roll_with(|sides| self.range(1..=sides))
+  }
+}
+
+impl RngCore for RandomNumberGenerator {
+  fn next_u32(&mut self) -> u32 {
+    self.rng.next_u32()
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    self.rng.next_u64()
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    self.rng.fill_bytes(dest)
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+    self.rng.try_fill_bytes(dest)
+  }
+}
+
+impl From<RandomState> for RandomNumberGenerator {
+  fn from(state: RandomState) -> Self {
+    Self::from_state(state)
+  }
+}
+
+impl From<RandomNumberGenerator> for RandomState {
+  fn from(rng: RandomNumberGenerator) -> Self {
+    RandomState(rng.rng)
+  }
 }
 
 #[cfg(test)]
@@ -121,11 +474,15 @@ mod test {
   #[test]
   fn test_inclusive_range_bounds() {
     let mut rng = RandomNumberGenerator::new();
+    let mut seen = [false; 11];
     for _ in 0..1000 {
-      let n = rng.range(1..10);
+      let n = rng.range(1..=10);
       assert!(n >= 1);
       assert!(n <= 10);
+      seen[n] = true;
     }
+    // Both ends of the range are reachable
+    assert!(seen[1] && seen[10]);
   }
 
   #[test]
@@ -142,6 +499,220 @@ mod test {
     });
   }
 
+  #[test]
+  fn test_snapshot_restore() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    rng.range(0..100);
+    let state = rng.snapshot();
+    let expected: Vec<u32> = (0..100).map(|_| rng.next()).collect();
+
+    let bytes = state.to_bytes().unwrap();
+    let mut from_bytes = RandomNumberGenerator::from_state(
+      RandomState::from_bytes(&bytes).unwrap()
+    );
+    let ron = ron::to_string(&from_bytes).unwrap();
+    let mut from_ron: RandomNumberGenerator = ron::from_str(&ron).unwrap();
+    let mut restored = RandomNumberGenerator::new();
+    restored.restore(&state);
+    for n in expected {
+      assert_eq!(from_bytes.next::<u32>(), n);
+      assert_eq!(from_ron.next::<u32>(), n);
+      assert_eq!(restored.next::<u32>(), n);
+    }
+  }
+
+  #[test]
+  fn test_fork() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let before = rng.snapshot();
+    let mut a = rng.fork("a");
+    let mut b = rng.fork("b");
+    assert!(rng.snapshot() == before);
+    let a: Vec<u32> = (0..10).map(|_| a.next()).collect();
+    let b: Vec<u32> = (0..10).map(|_| b.next()).collect();
+    assert_ne!(a, b);
+    rng.next::<u32>();
+    assert_ne!(rng.fork("a").next::<u32>(), a[0]);
+  }
+
+  fn mean_and_variance(samples: &[f32]) -> (f32, f32) {
+    let n = samples.len() as f32;
+    let mean = samples.iter().sum::<f32>() / n;
+    let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f32>() / n;
+    (mean, variance)
+  }
+
+  #[test]
+  fn test_normal() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let samples: Vec<f32> = (0..10_000).map(|_| rng.normal(5.0, 2.0)).collect();
+    let (mean, variance) = mean_and_variance(&samples);
+    assert!((mean - 5.0).abs() < 0.1);
+    assert!((variance - 4.0).abs() < 0.3);
+  }
+
+  #[test]
+  fn test_exponential() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let samples: Vec<f32> = (0..10_000).map(|_| rng.exponential(2.0)).collect();
+    assert!(samples.iter().all(|s| *s >= 0.0));
+    let (mean, _) = mean_and_variance(&samples);
+    assert!((mean - 0.5).abs() < 0.03);
+  }
+
+  #[test]
+  fn test_poisson_and_binomial() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let poisson: Vec<f32> = (0..10_000).map(|_| rng.poisson(3.0) as f32).collect();
+    let (mean, variance) = mean_and_variance(&poisson);
+    assert!((mean - 3.0).abs() < 0.1);
+    assert!((variance - 3.0).abs() < 0.3);
+    let binomial: Vec<u64> = (0..10_000).map(|_| rng.binomial(10, 0.3)).collect();
+    assert!(binomial.iter().all(|n| *n <= 10));
+    let binomial: Vec<f32> = binomial.iter().map(|n| *n as f32).collect();
+    let (mean, _) = mean_and_variance(&binomial);
+    assert!((mean - 3.0).abs() < 0.1);
+  }
+
+  #[test]
+  fn test_triangular_and_beta_bounds() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let triangular: Vec<f32> = (0..10_000).map(|_| rng.triangular(-1.0, 3.0, 2.0)).collect();
+    assert!(triangular.iter().all(|n| (-1.0..=3.0).contains(n)));
+    let (mean, _) = mean_and_variance(&triangular);
+    assert!((mean - 4.0 / 3.0).abs() < 0.05);
+    let beta: Vec<f32> = (0..10_000).map(|_| rng.beta(2.0, 5.0)).collect();
+    assert!(beta.iter().all(|n| (0.0..=1.0).contains(n)));
+    let (mean, _) = mean_and_variance(&beta);
+    assert!((mean - 2.0 / 7.0).abs() < 0.02);
+  }
+
+  #[test]
+  #[should_panic]
+  fn test_invalid_distribution() {
+    let mut rng = RandomNumberGenerator::new();
+    rng.triangular(0.0, 1.0, 2.0);
+  }
+
+  #[test]
+  fn test_shuffle_and_choose() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let mut deck: Vec<u32> = (0..52).collect();
+    rng.shuffle(&mut deck);
+    assert_ne!(deck, (0..52).collect::<Vec<u32>>());
+    deck.sort();
+    assert_eq!(deck, (0..52).collect::<Vec<u32>>());
+
+    assert!(rng.choose::<u32>(&[]).is_none());
+    assert!(deck.contains(rng.choose(&deck).unwrap()));
+    let mut hand = rng.choose_multiple(&deck, 5);
+    hand.sort();
+    hand.dedup();
+    assert_eq!(hand.len(), 5);
+    assert_eq!(rng.choose_multiple(&deck[..3], 5).len(), 3);
+  }
+
+  #[test]
+  fn test_sample() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let mut counts = [0; 10];
+    for _ in 0..10_000 {
+      for n in rng.sample(0..10, 2) {
+        counts[n] += 1;
+      }
+    }
+    // Each item should be picked about 2,000 times
+    assert!(counts.iter().all(|c| (1800..2200).contains(c)));
+  }
+
+  #[test]
+  fn test_shapes() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let center = Vec2::new(3.0, -2.0);
+    let mut in_inner_half = 0;
+    for _ in 0..10_000 {
+      assert!((rng.unit_vec2().length() - 1.0).abs() < 1e-4);
+      assert!((rng.unit_vec3().length() - 1.0).abs() < 1e-4);
+      let p = rng.point_in_circle(center, 2.0);
+      assert!(p.distance(center) <= 2.0 + 1e-4);
+      if p.distance(center) < 2.0 / std::f32::consts::SQRT_2 {
+        in_inner_half += 1;
+      }
+      let d = rng.point_in_annulus(center, 1.0, 2.0).distance(center);
+      assert!((1.0 - 1e-4..=2.0 + 1e-4).contains(&d));
+      assert!(Rect::new(-1.0, -1.0, 1.0, 1.0).contains(rng.point_in_rect(Rect::new(-1.0, -1.0, 1.0, 1.0))));
+    }
+    // Uniform by area: half the points fall within radius / sqrt(2)
+    assert!((4800..5200).contains(&in_inner_half));
+
+    // An L shape: nothing should land in the missing corner
+    let l_shape = [
+      Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 1.0),
+      Vec2::new(1.0, 1.0), Vec2::new(1.0, 2.0), Vec2::new(0.0, 2.0),
+    ];
+    for _ in 0..1000 {
+      let p = rng.point_in_polygon(&l_shape);
+      assert!((0.0..=2.0).contains(&p.x) && (0.0..=2.0).contains(&p.y));
+      assert!(p.x <= 1.0 || p.y <= 1.0);
+    }
+  }
+
+  #[test]
+  fn test_poisson_disc() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let area = Rect::new(-100.0, -50.0, 100.0, 50.0);
+    let points = rng.poisson_disc(area, 10.0);
+    assert!(points.len() > 100);
+    let existing = [Vec2::ZERO];
+    let more = rng.poisson_disc_around(area, 10.0, &existing);
+    for (i, a) in points.iter().enumerate() {
+      assert!(area.contains(*a));
+      assert!(points[i + 1..].iter().all(|b| a.distance(*b) >= 10.0));
+    }
+    assert!(more.iter().all(|p| p.distance(Vec2::ZERO) >= 10.0));
+    assert!(!more.contains(&Vec2::ZERO));
+  }
+
+  #[test]
+  fn test_poisson_disc_crowded_existing() {
+    // Closer together than one grid cell (10 / sqrt 2)
+    let existing = [Vec2::new(0.5, 0.5), Vec2::new(2.5, 2.5), Vec2::new(4.0, 1.0)];
+    let area = Rect::new(0.0, 0.0, 60.0, 60.0);
+    for seed in 0..20 {
+      let rng = &mut RandomNumberGenerator::seeded(seed);
+      let more = rng.poisson_disc_around(area, 10.0, &existing);
+      assert!(more.iter().all(|p| existing.iter().all(|e| p.distance(*e) >= 10.0)));
+    }
+    let rng = &mut RandomNumberGenerator::seeded(1);
+    let one = rng.poisson_disc_up_to(area, 10.0, &existing, 1);
+    assert_eq!(one.len(), 1);
+    assert!(existing.iter().all(|e| one[0].distance(*e) >= 10.0));
+    assert_eq!(rng.poisson_disc_up_to(area, 10.0, &[], 1).len(), 1);
+  }
+
+  #[test]
+  fn test_algorithms() {
+    let mut first = Vec::new();
+    for algorithm in Algorithm::ALL {
+      let mut a = RandomNumberGenerator::with_algorithm(algorithm, 1);
+      let mut b = RandomNumberGenerator::with_algorithm(algorithm, 1);
+      assert_eq!(a.algorithm(), algorithm);
+      assert_eq!(a.fork("stream").algorithm(), algorithm);
+      let n: u64 = a.next();
+      assert_eq!(n, b.next::<u64>());
+      first.push(n);
+
+      let state = a.snapshot();
+      assert_eq!(state.algorithm(), algorithm);
+      let restored = RandomNumberGenerator::from_state(
+        RandomState::from_bytes(&state.to_bytes().unwrap()).unwrap()
+      );
+      assert_eq!(restored.algorithm(), algorithm);
+    }
+    first.dedup();
+    assert_eq!(first.len(), Algorithm::ALL.len());
+  }
+
   #[test]
   fn test_next_types() {
     let mut rng = RandomNumberGenerator::new();
@@ -149,6 +720,19 @@ mod test {
     let _ = rng.next::<f32>();
   }
 
+  #[test]
+  fn test_dice_bounds() {
+    let mut rng = RandomNumberGenerator::new();
+    let dice = DiceExpression::parse("2d20kl1-1").unwrap();
+    for _ in 0..1000 {
+      let roll = rng.roll(&dice);
+      assert!(roll.total >= 0);
+      assert!(roll.total <= 19);
+      assert_eq!(roll.dice().filter(|d| d.kept).count(), 1);
+    }
+    assert!(rng.roll_dice("3d").is_err());
+  }
+
   #[test]
   fn test_float() {
     let mut rng = RandomNumberGenerator::new();
@@ -162,17 +746,3 @@ mod test {
     }
   }
 }
-
-/// `Random` is a Bevy plugin that inserts a `RandomNumberGenerator` 
-/// resource into your application.
-/// 
-/// Once you add the plugin (with `App::new().add_plugin(Random)`),
-/// you can access a random number generator in systems with
-/// `rng: ResMut<RandomNumberGenerator>`.
-pub struct RandomPlugin;
-
-impl bevy::prelude::Plugin for RandomPlugin {
-  fn build(&self, app: &mut bevy::prelude::App) {
-      app.insert_resource(RandomNumberGenerator::new());
-  }
-}
\ No newline at end of file
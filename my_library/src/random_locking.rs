@@ -1,17 +1,53 @@
-use rand::{Rng, SeedableRng, distributions::uniform::{SampleRange, SampleUniform}};
-use std::sync::Mutex;
-
-#[cfg(all(not(feature = "pcg"), not(feature = "xorshift")))]
-type RngCore = rand::prelude::StdRng;
-
-#[cfg(feature = "pcg")]
-type RngCore = rand_pcg::Pcg64Mcg;
-
-#[cfg(feature = "xorshift")]
-type RngCore = rand_xorshift::XorShiftRng;
+use rand::{
+  Rng, RngCore,
+  distributions::uniform::{SampleRange, SampleUniform},
+  seq::{IteratorRandom, SliceRandom},
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock};
+use std::thread::ThreadId;
+use std::hash::Hash;
+use bevy::{math::{Rect, Vec2, Vec3}, utils::HashMap};
+use crate::{
+  Algorithm, DiceExpression, DiceRoll, RandomState,
+  algorithm::Backend, distributions, random_streams::stream_seed, sampling,
+};
 
+/// `RandomNumberGenerator` holds random number generation state, and offers 
+/// random number generation services to your program.
+///
+/// This is the `locking` variant: the state sits behind a mutex, so
+/// every method takes `&self` and the generator can be shared in Bevy
+/// with `Res<RandomNumberGenerator>`. Systems that only read resources
+/// can run in parallel.
+///
+/// Every call locks the generator, so systems drawing many numbers at
+/// once should use [`RandomNumberGenerator::per_thread`], which hands
+/// each thread its own sub-generator derived from this one.
+///
+/// Like the plain variant, it defaults to PCG. To default to XorShift
+/// instead, enable the `xorshift` feature with `default-features = false`:
+/// `pcg` is a default feature, and wins when both are enabled. Any
+/// algorithm can also be chosen at runtime; see [`Algorithm`] and
+/// [`RandomNumberGenerator::with_algorithm`].
+///
+/// Both `RandomNumberGenerator` and `&RandomNumberGenerator` implement
+/// `rand::RngCore`, so a shared generator can drive anything generic
+/// over [`Rng`](crate::rand::Rng): pass `&mut &*rng` from a
+/// `Res<RandomNumberGenerator>`.
+///
+/// ## Example
+///
+/// ```
+/// use my_library::RandomNumberGenerator;
+/// let my_rng = RandomNumberGenerator::new();
+/// let random_number = my_rng.range(1..10);
+/// println!("{random_number}");
+/// ```
+#[derive(bevy::prelude::Resource)]
 pub struct RandomNumberGenerator {
-  rng: Mutex<RngCore>,
+  rng: Mutex<Backend>,
+  threads: RwLock<HashMap<ThreadId, Arc<RandomNumberGenerator>>>,
 }
 
 impl Default for RandomNumberGenerator {
@@ -20,32 +56,294 @@ impl Default for RandomNumberGenerator {
   }
 }
 
+impl Clone for RandomNumberGenerator {
+  fn clone(&self) -> Self {
+    Self::from_state(self.snapshot())
+  }
+}
+
 impl RandomNumberGenerator {
-  pub fn new() -> Self {
+  fn from_backend(backend: Backend) -> Self {
     Self {
-      rng: Mutex::new(RngCore::from_entropy()),
+      rng: Mutex::new(backend),
+      threads: RwLock::default(),
     }
   }
 
+  // A panic can't leave a generator half-updated (each step replaces
+  // its whole state), so a poisoned lock is still safe to use.
+  fn lock(&self) -> MutexGuard<'_, Backend> {
+    self.rng.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+
+  /// Creates a default `RandomNumberGenerator`, with a randomly 
+  /// selected starting seed.
+  pub fn new() -> Self {
+    Self::from_backend(Backend::from_entropy(Algorithm::default()))
+  }
+
+  /// Creates a new `RandomNumberGenerator`, with a user-specified random seed.
+  /// It will produce the same results each time (given the same requests).
   pub fn seeded(seed: u64) -> Self {
-    Self {
-      rng: Mutex::new(RngCore::seed_from_u64(seed)),
+    Self::from_backend(Backend::seed_from_u64(Algorithm::default(), seed))
+  }
+
+  /// Creates a `RandomNumberGenerator` using a specific [`Algorithm`].
+  pub fn with_algorithm(algorithm: Algorithm, seed: u64) -> Self {
+    Self::from_backend(Backend::seed_from_u64(algorithm, seed))
+  }
+
+  /// Creates a `RandomNumberGenerator` using a specific [`Algorithm`],
+  /// with a randomly selected starting seed.
+  pub fn new_with_algorithm(algorithm: Algorithm) -> Self {
+    Self::from_backend(Backend::from_entropy(algorithm))
+  }
+
+  /// The algorithm this generator uses.
+  pub fn algorithm(&self) -> Algorithm {
+    self.lock().algorithm()
+  }
+
+  /// Creates a `RandomNumberGenerator` that continues from a saved
+  /// [`RandomState`].
+  pub fn from_state(state: RandomState) -> Self {
+    Self::from_backend(state.0)
+  }
+
+  /// Captures the generator's current state. Restoring it later
+  /// replays the exact same sequence of numbers from this point.
+  pub fn snapshot(&self) -> RandomState {
+    RandomState(self.lock().clone())
+  }
+
+  /// Rewinds (or fast-forwards) the generator to a saved state.
+  /// Per-thread sub-generators are discarded, and derived again from
+  /// the restored state on their next use.
+  pub fn restore(&self, state: &RandomState) {
+    *self.lock() = state.0.clone();
+    self.threads.write().unwrap_or_else(PoisonError::into_inner).clear();
+  }
+
+  /// Creates an independent generator for stream `stream`, derived
+  /// from this generator's current state. The parent is not advanced.
+  pub fn fork<K: Hash + ?Sized>(&self, stream: &K) -> Self {
+    let rng = self.lock();
+    let base: u64 = rng.clone().gen();
+    Self::with_algorithm(rng.algorithm(), stream_seed(base, stream))
+  }
+
+  /// Returns this thread's sub-generator, creating it on first use.
+  ///
+  /// Each thread's generator is forked from this one (see
+  /// [`RandomNumberGenerator::fork`]), so it is still derived from the
+  /// seed, and nobody else ever locks it. Hold on to the returned
+  /// generator while drawing many numbers, rather than calling
+  /// `per_thread` each time.
+  ///
+  /// Sub-generators are numbered in the order threads first ask for
+  /// one, which Bevy's scheduler doesn't fix; use
+  /// [`RandomStreams`](crate::RandomStreams) when results must be
+  /// reproducible.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use my_library::RandomNumberGenerator;
+  /// let rng = RandomNumberGenerator::seeded(1);
+  /// let local = rng.per_thread();
+  /// let total: u32 = (0..100).map(|_| local.range(1..=6)).sum();
+  /// ```
+  pub fn per_thread(&self) -> Arc<Self> {
+    let id = std::thread::current().id();
+    if let Some(rng) = self.threads.read().unwrap_or_else(PoisonError::into_inner).get(&id) {
+      return rng.clone();
     }
+    let mut threads = self.threads.write().unwrap_or_else(PoisonError::into_inner);
+    let index = threads.len() as u64;
+    threads
+      .entry(id)
+      .or_insert_with(|| Arc::new(self.fork(&("thread", index))))
+      .clone()
   }
 
+  /// Generates a new random number of the requested type.
   pub fn next<T>(&self) -> T
   where rand::distributions::Standard: rand::prelude::Distribution<T>
   {
-    let mut lock = self.rng.lock().unwrap();
-    lock.gen()
+    self.lock().gen()
   }
 
+  /// Generates a random number within the specified range.
   pub fn range<T>(&self, range: impl SampleRange<T>) -> T
   where
     T: SampleUniform + PartialOrd,
   {
-    let mut lock = self.rng.lock().unwrap();
-    lock.gen_range(range)
+    self.lock().gen_range(range)
+  }
+
+  /// Shuffles a slice in place (Fisher-Yates).
+  pub fn shuffle<T>(&self, slice: &mut [T]) {
+    slice.shuffle(&mut *self.lock());
+  }
+
+  /// Picks a random element of a slice, or `None` if it is empty.
+  pub fn choose<'a, T>(&self, slice: &'a [T]) -> Option<&'a T> {
+    slice.choose(&mut *self.lock())
+  }
+
+  /// Picks `amount` distinct elements of a slice, in random order.
+  pub fn choose_multiple<'a, T>(&self, slice: &'a [T], amount: usize) -> Vec<&'a T> {
+    slice.choose_multiple(&mut *self.lock(), amount).collect()
+  }
+
+  /// Picks `amount` distinct items from an iterator using reservoir
+  /// sampling. The lock is held while the iterator is consumed.
+  pub fn sample<I: IntoIterator>(&self, iter: I, amount: usize) -> Vec<I::Item> {
+    iter.into_iter().choose_multiple(&mut *self.lock(), amount)
+  }
+
+  /// Samples a normal (Gaussian) distribution.
+  pub fn normal(&self, mean: f32, std_dev: f32) -> f32 {
+    self.lock().sample(distributions::normal(mean, std_dev))
+  }
+
+  /// Samples an exponential distribution with events at `rate`.
+  pub fn exponential(&self, rate: f32) -> f32 {
+    self.lock().sample(distributions::exponential(rate))
+  }
+
+  /// Samples a Poisson distribution with the given `mean`.
+  pub fn poisson(&self, mean: f32) -> u64 {
+    self.lock().sample(distributions::poisson(mean)) as u64
+  }
+
+  /// Samples a binomial distribution of `trials` attempts.
+  pub fn binomial(&self, trials: u64, probability: f64) -> u64 {
+    self.lock().sample(distributions::binomial(trials, probability))
+  }
+
+  /// Samples a triangular distribution peaking at `mode`.
+  pub fn triangular(&self, min: f32, max: f32, mode: f32) -> f32 {
+    self.lock().sample(distributions::triangular(min, max, mode))
+  }
+
+  /// Samples a beta distribution, returning a value in `0.0..=1.0`.
+  pub fn beta(&self, alpha: f32, beta: f32) -> f32 {
+    self.lock().sample(distributions::beta(alpha, beta))
+  }
+
+  /// Picks a random direction: a point on the unit circle.
+  pub fn unit_vec2(&self) -> Vec2 {
+    sampling::unit_vec2(&mut *self.lock())
+  }
+
+  /// Picks a random direction in 3D: a point on the unit sphere.
+  pub fn unit_vec3(&self) -> Vec3 {
+    sampling::unit_vec3(&mut *self.lock())
+  }
+
+  /// Picks a point inside a circle, uniformly by area.
+  pub fn point_in_circle(&self, center: Vec2, radius: f32) -> Vec2 {
+    sampling::in_annulus(&mut *self.lock(), center, 0.0, radius)
+  }
+
+  /// Picks a point inside a ring between `inner` and `outer` radius,
+  /// uniformly by area.
+  pub fn point_in_annulus(&self, center: Vec2, inner: f32, outer: f32) -> Vec2 {
+    sampling::in_annulus(&mut *self.lock(), center, inner, outer)
+  }
+
+  /// Picks a point inside a rectangle.
+  pub fn point_in_rect(&self, rect: Rect) -> Vec2 {
+    sampling::in_rect(&mut *self.lock(), rect)
+  }
+
+  /// Picks a point inside a simple polygon, uniformly by area.
+  pub fn point_in_polygon(&self, polygon: &[Vec2]) -> Vec2 {
+    sampling::in_polygon(&mut *self.lock(), polygon)
+  }
+
+  /// Fills `area` with points at least `min_distance` apart (Poisson-disc
+  /// sampling). The lock is held until the area is full.
+  pub fn poisson_disc(&self, area: Rect, min_distance: f32) -> Vec<Vec2> {
+    sampling::poisson_disc(&mut *self.lock(), area, min_distance, &[], usize::MAX)
+  }
+
+  /// Poisson-disc sampling that keeps clear of `existing` points. Only
+  /// the new points are returned.
+  pub fn poisson_disc_around(&self, area: Rect, min_distance: f32, existing: &[Vec2]) -> Vec<Vec2> {
+    sampling::poisson_disc(&mut *self.lock(), area, min_distance, existing, usize::MAX)
+  }
+
+  /// Like [`RandomNumberGenerator::poisson_disc_around`], but stops
+  /// after `count` new points, which is much faster than filling the
+  /// whole area to use a few of them.
+  pub fn poisson_disc_up_to(&self, area: Rect, min_distance: f32, existing: &[Vec2], count: usize) -> Vec<Vec2> {
+    sampling::poisson_disc(&mut *self.lock(), area, min_distance, existing, count)
+  }
+
+  /// Rolls dice described in dice notation, such as `"3d6+2"`.
+  /// See [`DiceExpression`] for the full notation.
+  pub fn roll_dice(&self, expression: &str) -> anyhow::Result<DiceRoll> {
+    let dice = DiceExpression::parse(expression)?;
+    Ok(self.roll(&dice))
+  }
+
+  /// Rolls a pre-parsed [`DiceExpression`], holding the lock for
+  /// the whole roll.
+  pub fn roll(&self, dice: &DiceExpression) -> DiceRoll {
+    let mut lock = self.lock();
+    dice.roll_with(|sides| lock.gen_range(1..=sides))
+  }
+}
+
+impl RngCore for RandomNumberGenerator {
+  fn next_u32(&mut self) -> u32 {
+    self.rng.get_mut().unwrap_or_else(PoisonError::into_inner).next_u32()
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    self.rng.get_mut().unwrap_or_else(PoisonError::into_inner).next_u64()
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    self.rng.get_mut().unwrap_or_else(PoisonError::into_inner).fill_bytes(dest)
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+    self.rng.get_mut().unwrap_or_else(PoisonError::into_inner).try_fill_bytes(dest)
+  }
+}
+
+// Each call takes the lock, so a long run of draws through a shared
+// reference costs one lock per number
+impl RngCore for &RandomNumberGenerator {
+  fn next_u32(&mut self) -> u32 {
+    self.lock().next_u32()
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    self.lock().next_u64()
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    self.lock().fill_bytes(dest)
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+    self.lock().try_fill_bytes(dest)
+  }
+}
+
+impl Serialize for RandomNumberGenerator {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.snapshot().serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for RandomNumberGenerator {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    RandomState::deserialize(deserializer).map(Self::from_state)
   }
 }
 
@@ -66,11 +364,15 @@ mod test {
   #[test]
   fn test_inclusive_range_bounds() {
     let rng = RandomNumberGenerator::new();
+    let mut seen = [false; 11];
     for _ in 0..1000 {
-      let n = rng.range(1..10);
+      let n = rng.range(1..=10);
       assert!(n >= 1);
       assert!(n <= 10);
+      seen[n] = true;
     }
+    // Both ends of the range are reachable
+    assert!(seen[1] && seen[10]);
   }
 
   #[test]
@@ -87,13 +389,288 @@ mod test {
     });
   }
 
+  #[test]
+  fn test_snapshot_restore() {
+    let rng = RandomNumberGenerator::seeded(1);
+    rng.range(0..100);
+    let state = rng.snapshot();
+    let expected: Vec<u32> = (0..100).map(|_| rng.next()).collect();
+
+    let bytes = state.to_bytes().unwrap();
+    let from_bytes = RandomNumberGenerator::from_state(
+      RandomState::from_bytes(&bytes).unwrap()
+    );
+    let ron = ron::to_string(&from_bytes).unwrap();
+    let from_ron: RandomNumberGenerator = ron::from_str(&ron).unwrap();
+    let restored = RandomNumberGenerator::new();
+    restored.restore(&state);
+    for n in expected {
+      assert_eq!(from_bytes.next::<u32>(), n);
+      assert_eq!(from_ron.next::<u32>(), n);
+      assert_eq!(restored.next::<u32>(), n);
+    }
+  }
+
+  #[test]
+  fn test_fork() {
+    let rng = RandomNumberGenerator::seeded(1);
+    let before = rng.snapshot();
+    let a = rng.fork("a");
+    let b = rng.fork("b");
+    assert!(rng.snapshot() == before);
+    let a: Vec<u32> = (0..10).map(|_| a.next()).collect();
+    let b: Vec<u32> = (0..10).map(|_| b.next()).collect();
+    assert_ne!(a, b);
+    rng.next::<u32>();
+    assert_ne!(rng.fork("a").next::<u32>(), a[0]);
+  }
+
+  fn mean_and_variance(samples: &[f32]) -> (f32, f32) {
+    let n = samples.len() as f32;
+    let mean = samples.iter().sum::<f32>() / n;
+    let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f32>() / n;
+    (mean, variance)
+  }
+
+  #[test]
+  fn test_normal() {
+    let rng = RandomNumberGenerator::seeded(1);
+    let samples: Vec<f32> = (0..10_000).map(|_| rng.normal(5.0, 2.0)).collect();
+    let (mean, variance) = mean_and_variance(&samples);
+    assert!((mean - 5.0).abs() < 0.1);
+    assert!((variance - 4.0).abs() < 0.3);
+  }
+
+  #[test]
+  fn test_exponential() {
+    let rng = RandomNumberGenerator::seeded(1);
+    let samples: Vec<f32> = (0..10_000).map(|_| rng.exponential(2.0)).collect();
+    assert!(samples.iter().all(|s| *s >= 0.0));
+    let (mean, _) = mean_and_variance(&samples);
+    assert!((mean - 0.5).abs() < 0.03);
+  }
+
+  #[test]
+  fn test_poisson_and_binomial() {
+    let rng = RandomNumberGenerator::seeded(1);
+    let poisson: Vec<f32> = (0..10_000).map(|_| rng.poisson(3.0) as f32).collect();
+    let (mean, variance) = mean_and_variance(&poisson);
+    assert!((mean - 3.0).abs() < 0.1);
+    assert!((variance - 3.0).abs() < 0.3);
+    let binomial: Vec<u64> = (0..10_000).map(|_| rng.binomial(10, 0.3)).collect();
+    assert!(binomial.iter().all(|n| *n <= 10));
+    let binomial: Vec<f32> = binomial.iter().map(|n| *n as f32).collect();
+    let (mean, _) = mean_and_variance(&binomial);
+    assert!((mean - 3.0).abs() < 0.1);
+  }
+
+  #[test]
+  fn test_triangular_and_beta_bounds() {
+    let rng = RandomNumberGenerator::seeded(1);
+    let triangular: Vec<f32> = (0..10_000).map(|_| rng.triangular(-1.0, 3.0, 2.0)).collect();
+    assert!(triangular.iter().all(|n| (-1.0..=3.0).contains(n)));
+    let (mean, _) = mean_and_variance(&triangular);
+    assert!((mean - 4.0 / 3.0).abs() < 0.05);
+    let beta: Vec<f32> = (0..10_000).map(|_| rng.beta(2.0, 5.0)).collect();
+    assert!(beta.iter().all(|n| (0.0..=1.0).contains(n)));
+    let (mean, _) = mean_and_variance(&beta);
+    assert!((mean - 2.0 / 7.0).abs() < 0.02);
+  }
+
+  #[test]
+  #[should_panic]
+  fn test_invalid_distribution() {
+    let rng = RandomNumberGenerator::new();
+    rng.triangular(0.0, 1.0, 2.0);
+  }
+
+  #[test]
+  fn test_shuffle_and_choose() {
+    let rng = RandomNumberGenerator::seeded(1);
+    let mut deck: Vec<u32> = (0..52).collect();
+    rng.shuffle(&mut deck);
+    assert_ne!(deck, (0..52).collect::<Vec<u32>>());
+    deck.sort();
+    assert_eq!(deck, (0..52).collect::<Vec<u32>>());
+
+    assert!(rng.choose::<u32>(&[]).is_none());
+    assert!(deck.contains(rng.choose(&deck).unwrap()));
+    let mut hand = rng.choose_multiple(&deck, 5);
+    hand.sort();
+    hand.dedup();
+    assert_eq!(hand.len(), 5);
+    assert_eq!(rng.choose_multiple(&deck[..3], 5).len(), 3);
+  }
+
+  #[test]
+  fn test_sample() {
+    let rng = RandomNumberGenerator::seeded(1);
+    let mut counts = [0; 10];
+    for _ in 0..10_000 {
+      for n in rng.sample(0..10, 2) {
+        counts[n] += 1;
+      }
+    }
+    // Each item should be picked about 2,000 times
+    assert!(counts.iter().all(|c| (1800..2200).contains(c)));
+  }
+
+  #[test]
+  fn test_shapes() {
+    let rng = RandomNumberGenerator::seeded(1);
+    let center = Vec2::new(3.0, -2.0);
+    let mut in_inner_half = 0;
+    for _ in 0..10_000 {
+      assert!((rng.unit_vec2().length() - 1.0).abs() < 1e-4);
+      assert!((rng.unit_vec3().length() - 1.0).abs() < 1e-4);
+      let p = rng.point_in_circle(center, 2.0);
+      assert!(p.distance(center) <= 2.0 + 1e-4);
+      if p.distance(center) < 2.0 / std::f32::consts::SQRT_2 {
+        in_inner_half += 1;
+      }
+      let d = rng.point_in_annulus(center, 1.0, 2.0).distance(center);
+      assert!((1.0 - 1e-4..=2.0 + 1e-4).contains(&d));
+      assert!(Rect::new(-1.0, -1.0, 1.0, 1.0).contains(rng.point_in_rect(Rect::new(-1.0, -1.0, 1.0, 1.0))));
+    }
+    // Uniform by area: half the points fall within radius / sqrt(2)
+    assert!((4800..5200).contains(&in_inner_half));
+
+    // An L shape: nothing should land in the missing corner
+    let l_shape = [
+      Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 1.0),
+      Vec2::new(1.0, 1.0), Vec2::new(1.0, 2.0), Vec2::new(0.0, 2.0),
+    ];
+    for _ in 0..1000 {
+      let p = rng.point_in_polygon(&l_shape);
+      assert!((0.0..=2.0).contains(&p.x) && (0.0..=2.0).contains(&p.y));
+      assert!(p.x <= 1.0 || p.y <= 1.0);
+    }
+  }
+
+  #[test]
+  fn test_poisson_disc() {
+    let rng = RandomNumberGenerator::seeded(1);
+    let area = Rect::new(-100.0, -50.0, 100.0, 50.0);
+    let points = rng.poisson_disc(area, 10.0);
+    assert!(points.len() > 100);
+    let existing = [Vec2::ZERO];
+    let more = rng.poisson_disc_around(area, 10.0, &existing);
+    for (i, a) in points.iter().enumerate() {
+      assert!(area.contains(*a));
+      assert!(points[i + 1..].iter().all(|b| a.distance(*b) >= 10.0));
+    }
+    assert!(more.iter().all(|p| p.distance(Vec2::ZERO) >= 10.0));
+    assert!(!more.contains(&Vec2::ZERO));
+  }
+
+  #[test]
+  fn test_poisson_disc_crowded_existing() {
+    // Closer together than one grid cell (10 / sqrt 2)
+    let existing = [Vec2::new(0.5, 0.5), Vec2::new(2.5, 2.5), Vec2::new(4.0, 1.0)];
+    let area = Rect::new(0.0, 0.0, 60.0, 60.0);
+    for seed in 0..20 {
+      let rng = &mut RandomNumberGenerator::seeded(seed);
+      let more = rng.poisson_disc_around(area, 10.0, &existing);
+      assert!(more.iter().all(|p| existing.iter().all(|e| p.distance(*e) >= 10.0)));
+    }
+    let rng = &mut RandomNumberGenerator::seeded(1);
+    let one = rng.poisson_disc_up_to(area, 10.0, &existing, 1);
+    assert_eq!(one.len(), 1);
+    assert!(existing.iter().all(|e| one[0].distance(*e) >= 10.0));
+    assert_eq!(rng.poisson_disc_up_to(area, 10.0, &[], 1).len(), 1);
+  }
+
+  #[test]
+  fn test_algorithms() {
+    let mut first = Vec::new();
+    for algorithm in Algorithm::ALL {
+      let a = RandomNumberGenerator::with_algorithm(algorithm, 1);
+      let b = RandomNumberGenerator::with_algorithm(algorithm, 1);
+      assert_eq!(a.algorithm(), algorithm);
+      assert_eq!(a.fork("stream").algorithm(), algorithm);
+      let n: u64 = a.next();
+      assert_eq!(n, b.next::<u64>());
+      first.push(n);
+
+      let state = a.snapshot();
+      assert_eq!(state.algorithm(), algorithm);
+      let restored = RandomNumberGenerator::from_state(
+        RandomState::from_bytes(&state.to_bytes().unwrap()).unwrap()
+      );
+      assert_eq!(restored.algorithm(), algorithm);
+    }
+    first.dedup();
+    assert_eq!(first.len(), Algorithm::ALL.len());
+  }
+
+  #[test]
+  fn test_per_thread() {
+    let rng = RandomNumberGenerator::seeded(1);
+    let local = rng.per_thread();
+    assert!(Arc::ptr_eq(&local, &rng.per_thread()));
+    let firsts: Vec<u64> = std::thread::scope(|scope| {
+      let workers: Vec<_> = (0..4)
+        .map(|_| scope.spawn(|| rng.per_thread().next::<u64>()))
+        .collect();
+      workers.into_iter().map(|w| w.join().unwrap()).collect()
+    });
+    let mut all = firsts.clone();
+    all.push(local.next());
+    all.sort();
+    all.dedup();
+    assert_eq!(all.len(), 5);
+
+    rng.restore(&RandomNumberGenerator::seeded(1).snapshot());
+    assert!(!Arc::ptr_eq(&local, &rng.per_thread()));
+  }
+
+  #[test]
+  fn test_poisoned_lock() {
+    let rng = RandomNumberGenerator::seeded(1);
+    let panicked = std::thread::scope(|scope| {
+      scope.spawn(|| {
+        let _lock = rng.lock();
+        panic!("poison the lock");
+      }).join().is_err()
+    });
+    assert!(panicked && rng.rng.is_poisoned());
+    let n = rng.range(1..=6);
+    assert!((1..=6).contains(&n));
+  }
+
+  #[test]
+  fn test_shared_resource() {
+    use bevy::prelude::*;
+    fn roll(rng: Res<RandomNumberGenerator>, mut rolls: Local<u32>) {
+      *rolls += rng.per_thread().range(1..=6);
+      assert!(rng.range(1..=6) <= 6);
+    }
+    App::new()
+      .insert_resource(RandomNumberGenerator::seeded(1))
+      .add_systems(Update, (roll, roll))
+      .update();
+  }
+
   #[test]
   fn test_next_types() {
-    let mut rng = RandomNumberGenerator::new();
+    let rng = RandomNumberGenerator::new();
     let _ : i32 = rng.next();
     let _ = rng.next::<f32>();
   }
 
+  #[test]
+  fn test_dice_bounds() {
+    let rng = RandomNumberGenerator::new();
+    let dice = DiceExpression::parse("2d20kl1-1").unwrap();
+    for _ in 0..1000 {
+      let roll = rng.roll(&dice);
+      assert!(roll.total >= 0);
+      assert!(roll.total <= 19);
+      assert_eq!(roll.dice().filter(|d| d.kept).count(), 1);
+    }
+    assert!(rng.roll_dice("3d").is_err());
+  }
+
   #[test]
   fn test_float() {
     let rng = RandomNumberGenerator::new();
@@ -106,12 +683,13 @@ mod test {
       assert!(!n.is_nan());
     }
   }
-}
-
-pub struct RandomPlugin;
-
-impl bevy::prelude::Plugin for RandomPlugin {
-  fn build(&self, app: &mut bevy::prelude::App) {
-      app.insert_resource(RandomNumberGenerator::new());
+  #[test]
+  fn test_shared_reference_drives_generic_code() {
+    let shared = RandomNumberGenerator::seeded(1);
+    let mut owned = RandomNumberGenerator::seeded(1);
+    let mut table = crate::WeightedTable::new().with("a", 1).with("b", 1).with("c", 1);
+    let a: Vec<_> = (0..20).map(|_| *table.pick(&mut &shared).unwrap()).collect();
+    let b: Vec<_> = (0..20).map(|_| *table.pick(&mut owned).unwrap()).collect();
+    assert_eq!(a, b);
   }
-}
\ No newline at end of file
+}
@@ -0,0 +1,233 @@
+use bevy::prelude::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::{RandomNumberGenerator, RandomStreams, random_streams::stream_seed};
+
+/// Where [`RandomPlugin`] gets its seed from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SeedPolicy {
+  /// A random seed, different every run.
+  Entropy,
+  /// Always the same seed.
+  Fixed(u64),
+  /// Read the seed from an environment variable, e.g. `FLAPPY_SEED=42`.
+  Env(&'static str),
+  /// Read the seed from a command line argument, given either as
+  /// `--seed 42` or `--seed=42`.
+  Arg(&'static str),
+  /// A seed derived from today's (UTC) date, so every player gets the
+  /// same game on the same day.
+  Daily,
+}
+
+impl SeedPolicy {
+  /// Returns the seed this policy selects, or `None` if it doesn't
+  /// apply (for example, the environment variable isn't set).
+  ///
+  /// Seeds given as text are usually numbers, but any other text is
+  /// hashed into a seed---so `--seed banana` works too.
+  pub fn seed(&self) -> Option<u64> {
+    match self {
+      SeedPolicy::Entropy => Some(rand::random()),
+      SeedPolicy::Fixed(seed) => Some(*seed),
+      SeedPolicy::Env(name) => std::env::var(name).ok().map(|text| parse_seed(&text)),
+      SeedPolicy::Arg(name) => find_arg(std::env::args(), name).map(|text| parse_seed(&text)),
+      SeedPolicy::Daily => Some(daily_seed(SystemTime::now())),
+    }
+  }
+}
+
+fn parse_seed(text: &str) -> u64 {
+  let text = text.trim();
+  text.parse().unwrap_or_else(|_| stream_seed(0, text))
+}
+
+fn find_arg(args: impl IntoIterator<Item = String>, name: &str) -> Option<String> {
+  let mut args = args.into_iter();
+  while let Some(arg) = args.next() {
+    if arg == name {
+      return args.next();
+    }
+    if let Some(value) = arg.strip_prefix(name).and_then(|rest| rest.strip_prefix('=')) {
+      return Some(value.to_string());
+    }
+  }
+  None
+}
+
+fn daily_seed(now: SystemTime) -> u64 {
+  let days = now
+    .duration_since(UNIX_EPOCH)
+    .map(|since| since.as_secs() / 86_400)
+    .unwrap_or(0);
+  stream_seed(days, "daily")
+}
+
+/// The seed [`RandomPlugin`] chose at startup. Show it in bug reports
+/// (or on screen) so a run can be replayed with `SeedPolicy::Fixed`.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RandomSeed(pub u64);
+
+/// The system set that reseeds the random number generators when a
+/// state selected with [`RandomPlugin::reseed_on_enter`] is entered.
+/// Order `OnEnter` systems that use random numbers after it, e.g.
+/// `setup.after(ReseedRandom)`.
+#[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ReseedRandom;
+
+type ReseedHook = Box<dyn Fn(&mut App) + Send + Sync>;
+
+/// `Random` is a Bevy plugin that inserts a `RandomNumberGenerator`
+/// and a `RandomStreams` resource into your application, both seeded
+/// from the same [`RandomSeed`].
+///
+/// Once you add the plugin (with `App::new().add_plugins(RandomPlugin::new())`),
+/// you can access a random number generator in systems with
+/// `rng: ResMut<RandomNumberGenerator>` (or `Res` with the `locking`
+/// feature), or independent per-system streams with
+/// `streams: ResMut<RandomStreams>`.
+///
+/// The seed comes from the first [`SeedPolicy`] that provides one,
+/// falling back to entropy, and is logged at startup.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// use my_library::{RandomPlugin, RandomSeed, SeedPolicy};
+///
+/// #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default, States)]
+/// enum GamePhase { #[default] Playing }
+///
+/// let mut app = App::new();
+/// app.add_state::<GamePhase>()
+///   .add_plugins(RandomPlugin::new()
+///     .with_seed(SeedPolicy::Arg("--seed"))
+///     .with_seed(SeedPolicy::Fixed(42))
+///     .reseed_on_enter(GamePhase::Playing));
+/// assert_eq!(app.world.resource::<RandomSeed>().0, 42);
+/// ```
+pub struct RandomPlugin {
+  policies: Vec<SeedPolicy>,
+  reseed: Vec<ReseedHook>,
+}
+
+impl Default for RandomPlugin {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl RandomPlugin {
+  /// Creates a plugin that seeds from entropy.
+  pub fn new() -> Self {
+    Self {
+      policies: Vec::new(),
+      reseed: Vec::new(),
+    }
+  }
+
+  /// Adds a seed policy. Policies are tried in the order they were
+  /// added; the first one that provides a seed wins.
+  pub fn with_seed(mut self, policy: SeedPolicy) -> Self {
+    self.policies.push(policy);
+    self
+  }
+
+  /// Restarts both generators from the chosen seed every time `state`
+  /// is entered, so each round of a game plays out the same way.
+  pub fn reseed_on_enter<T: States>(mut self, state: T) -> Self {
+    self.reseed.push(Box::new(move |app: &mut App| {
+      app.add_systems(OnEnter(state.clone()), reseed.in_set(ReseedRandom));
+    }));
+    self
+  }
+
+  fn choose_seed(&self) -> (u64, SeedPolicy) {
+    self.policies
+      .iter()
+      .find_map(|policy| policy.seed().map(|seed| (seed, policy.clone())))
+      .unwrap_or_else(|| (rand::random(), SeedPolicy::Entropy))
+  }
+}
+
+impl Plugin for RandomPlugin {
+  fn build(&self, app: &mut App) {
+    let (seed, policy) = self.choose_seed();
+    info!("Random seed: {seed} (from {policy:?})");
+    app.insert_resource(RandomSeed(seed));
+    app.insert_resource(RandomNumberGenerator::seeded(seed));
+    app.insert_resource(RandomStreams::seeded(seed));
+    for hook in &self.reseed {
+      hook(app);
+    }
+  }
+}
+
+fn reseed(
+  seed: Res<RandomSeed>,
+  mut rng: ResMut<RandomNumberGenerator>,
+  mut streams: ResMut<RandomStreams>,
+) {
+  *rng = RandomNumberGenerator::with_algorithm(rng.algorithm(), seed.0);
+  streams.reseed(seed.0);
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_seed_parsing() {
+    let args = |list: &[&str]| list.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+    assert_eq!(find_arg(args(&["game", "--seed", "42"]), "--seed"), Some("42".to_string()));
+    assert_eq!(find_arg(args(&["game", "--seed=7"]), "--seed"), Some("7".to_string()));
+    assert_eq!(find_arg(args(&["game", "--seeds=7"]), "--seed"), None);
+    assert_eq!(find_arg(args(&["game", "--seed"]), "--seed"), None);
+    assert_eq!(parse_seed(" 42 "), 42);
+    assert_eq!(parse_seed("banana"), parse_seed("banana"));
+    assert_ne!(parse_seed("banana"), parse_seed("apple"));
+  }
+
+  #[test]
+  fn test_daily_seed() {
+    let day = UNIX_EPOCH + std::time::Duration::from_secs(19_000 * 86_400);
+    let later = day + std::time::Duration::from_secs(86_399);
+    let tomorrow = day + std::time::Duration::from_secs(86_400);
+    assert_eq!(daily_seed(day), daily_seed(later));
+    assert_ne!(daily_seed(day), daily_seed(tomorrow));
+  }
+
+  #[test]
+  fn test_policy_order() {
+    let plugin = RandomPlugin::new()
+      .with_seed(SeedPolicy::Env("MY_LIBRARY_TEST_UNSET_SEED"))
+      .with_seed(SeedPolicy::Fixed(3))
+      .with_seed(SeedPolicy::Fixed(4));
+    assert_eq!(plugin.choose_seed(), (3, SeedPolicy::Fixed(3)));
+  }
+
+  #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default, States)]
+  enum Phase {
+    #[default] Playing,
+    Paused,
+  }
+
+  #[test]
+  fn test_reseed_on_enter() {
+    let mut app = App::new();
+    app.add_state::<Phase>()
+      .add_plugins(RandomPlugin::new()
+        .with_seed(SeedPolicy::Fixed(1))
+        .reseed_on_enter(Phase::Playing));
+    fn first_wall(app: &mut App) -> u64 {
+      app.update();
+      app.world.resource_mut::<RandomStreams>().stream("walls").next()
+    }
+    let a = first_wall(&mut app);
+    app.world.resource_mut::<NextState<Phase>>().set(Phase::Paused);
+    app.update();
+    app.world.resource_mut::<NextState<Phase>>().set(Phase::Playing);
+    let b = first_wall(&mut app);
+    assert_eq!(a, b);
+  }
+}
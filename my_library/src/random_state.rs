@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use crate::{Algorithm, algorithm::Backend};
+
+/// `RandomState` is a snapshot of a [`RandomNumberGenerator`](crate::RandomNumberGenerator)'s
+/// internal state.
+///
+/// Unlike a seed, restoring a `RandomState` resumes the sequence exactly
+/// where the snapshot was taken---so a saved game or a recorded replay
+/// keeps producing the same numbers after it is loaded.
+///
+/// A state records which [`Algorithm`] produced it, and restoring it
+/// switches the generator to that algorithm.
+///
+/// ## Example
+///
+/// ```
+/// use my_library::{RandomNumberGenerator, RandomState};
+/// let mut rng = RandomNumberGenerator::seeded(1);
+/// let saved = rng.snapshot().to_ron().unwrap();
+/// let expected: u32 = rng.next();
+///
+/// let mut restored = RandomNumberGenerator::from_state(
+///   RandomState::from_ron(&saved).unwrap()
+/// );
+/// assert_eq!(restored.next::<u32>(), expected);
+/// ```
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RandomState(pub(crate) Backend);
+
+impl RandomState {
+  /// The algorithm this state belongs to.
+  pub fn algorithm(&self) -> Algorithm {
+    self.0.algorithm()
+  }
+
+  /// Encodes the state in a compact binary form.
+  pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+    Ok(bincode::serialize(self)?)
+  }
+
+  /// Decodes a state produced by [`RandomState::to_bytes`].
+  pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+    Ok(bincode::deserialize(bytes)?)
+  }
+
+  /// Encodes the state as RON text.
+  pub fn to_ron(&self) -> anyhow::Result<String> {
+    Ok(ron::to_string(self)?)
+  }
+
+  /// Decodes a state produced by [`RandomState::to_ron`].
+  pub fn from_ron(ron: &str) -> anyhow::Result<Self> {
+    Ok(ron::from_str(ron)?)
+  }
+}
@@ -0,0 +1,156 @@
+use std::hash::{Hash, Hasher};
+use bevy::{prelude::Resource, utils::HashMap};
+use crate::{Algorithm, RandomNumberGenerator};
+
+// FNV-1a. Unlike `DefaultHasher`, its output is stable across Rust
+// releases, so a stream keeps its sequence when the toolchain changes.
+struct StableHasher(u64);
+
+impl Hasher for StableHasher {
+  fn finish(&self) -> u64 {
+    self.0
+  }
+
+  fn write(&mut self, bytes: &[u8]) {
+    for byte in bytes {
+      self.0 ^= *byte as u64;
+      self.0 = self.0.wrapping_mul(0x100_0000_01b3);
+    }
+  }
+}
+
+// SplitMix64 finalizer: spreads nearby inputs (stream 1, stream 2...)
+// across the whole seed space.
+pub(crate) fn mix64(mut z: u64) -> u64 {
+  z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+  z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+  z ^ (z >> 31)
+}
+
+/// Derives the seed of stream `key` from a master seed.
+pub(crate) fn stream_seed<K: Hash + ?Sized>(seed: u64, key: &K) -> u64 {
+  let mut hasher = StableHasher(0xcbf2_9ce4_8422_2325);
+  key.hash(&mut hasher);
+  mix64(seed ^ mix64(hasher.finish()))
+}
+
+/// `RandomStreams` hands out independent [`RandomNumberGenerator`]s,
+/// all derived from one master seed.
+///
+/// Each stream is identified by a key---a label such as `"walls"`, an
+/// `Entity`, or anything else that implements `Hash`. A stream's
+/// sequence depends only on the master seed and its key, so adding a
+/// random call to one system no longer changes the results of every
+/// other system.
+///
+/// `RandomPlugin` inserts a `RandomStreams` resource alongside
+/// `RandomNumberGenerator`.
+///
+/// ## Example
+///
+/// ```
+/// use my_library::RandomStreams;
+/// let mut streams = RandomStreams::seeded(1);
+/// let wall = streams.stream("walls").range(-5..5);
+/// streams.stream("particles").range(0..100); // Doesn't affect walls
+///
+/// let mut replay = RandomStreams::seeded(1);
+/// assert_eq!(replay.stream("walls").range(-5..5), wall);
+/// ```
+#[derive(Resource)]
+pub struct RandomStreams {
+  seed: u64,
+  algorithm: Algorithm,
+  streams: HashMap<u64, RandomNumberGenerator>,
+}
+
+impl Default for RandomStreams {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl RandomStreams {
+  /// Creates a set of streams with a randomly selected master seed.
+  pub fn new() -> Self {
+    Self::seeded(rand::random())
+  }
+
+  /// Creates a set of streams from a master seed.
+  pub fn seeded(seed: u64) -> Self {
+    Self::with_algorithm(Algorithm::default(), seed)
+  }
+
+  /// Creates a set of streams from a master seed, whose generators
+  /// all use `algorithm`.
+  pub fn with_algorithm(algorithm: Algorithm, seed: u64) -> Self {
+    Self {
+      seed,
+      algorithm,
+      streams: HashMap::new(),
+    }
+  }
+
+  /// The master seed all streams are derived from.
+  pub fn seed(&self) -> u64 {
+    self.seed
+  }
+
+  /// Returns the generator for stream `key`, creating it on first use.
+  /// Later calls with the same key continue the same sequence.
+  pub fn stream<K: Hash + ?Sized>(&mut self, key: &K) -> &mut RandomNumberGenerator {
+    let seed = stream_seed(self.seed, key);
+    let algorithm = self.algorithm;
+    self.streams
+      .entry(seed)
+      .or_insert_with(|| RandomNumberGenerator::with_algorithm(algorithm, seed))
+  }
+
+  /// Creates a fresh generator for stream `key`, starting from the
+  /// beginning of its sequence. It only needs shared access, so
+  /// systems can derive their own generator (e.g. into a `Local`)
+  /// from `Res<RandomStreams>` and still run in parallel.
+  pub fn derive<K: Hash + ?Sized>(&self, key: &K) -> RandomNumberGenerator {
+    RandomNumberGenerator::with_algorithm(self.algorithm, stream_seed(self.seed, key))
+  }
+
+  /// Forgets stream `key`, for example when its entity is despawned.
+  /// The next call to [`RandomStreams::stream`] restarts it.
+  pub fn remove<K: Hash + ?Sized>(&mut self, key: &K) {
+    self.streams.remove(&stream_seed(self.seed, key));
+  }
+
+  /// Replaces the master seed and restarts every stream.
+  pub fn reseed(&mut self, seed: u64) {
+    self.seed = seed;
+    self.streams.clear();
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_streams_are_independent() {
+    let mut a = RandomStreams::seeded(42);
+    let mut b = RandomStreams::seeded(42);
+    b.stream("particles").range(0..100);
+    for _ in 0..100 {
+      assert_eq!(
+        a.stream("walls").range(0..u32::MAX),
+        b.stream("walls").range(0..u32::MAX),
+      );
+    }
+  }
+
+  #[test]
+  fn test_stream_keys() {
+    let mut streams = RandomStreams::seeded(42);
+    let entity = bevy::prelude::Entity::from_raw(7);
+    let from_entity: u64 = streams.stream(&entity).next();
+    let from_label: u64 = streams.stream("walls").next();
+    assert_ne!(from_entity, from_label);
+    assert_eq!(streams.derive(&entity).next::<u64>(), from_entity);
+  }
+}
@@ -0,0 +1,157 @@
+//! Geometric sampling behind the shape methods on
+//! `RandomNumberGenerator`, shared by the plain and locking variants.
+//! Like the distributions, invalid shapes are programming errors and
+//! panic with a message naming the method.
+use bevy::math::{Rect, Vec2, Vec3};
+use rand::Rng;
+use std::f32::consts::TAU;
+
+pub(crate) fn unit_vec2<R: Rng + ?Sized>(rng: &mut R) -> Vec2 {
+  let angle = rng.gen_range(0.0..TAU);
+  Vec2::new(angle.cos(), angle.sin())
+}
+
+pub(crate) fn unit_vec3<R: Rng + ?Sized>(rng: &mut R) -> Vec3 {
+  // Archimedes: z is uniform on a sphere's axis
+  let z: f32 = rng.gen_range(-1.0..=1.0);
+  let ring = (1.0 - z * z).sqrt();
+  unit_vec2(rng).extend(0.0) * ring + Vec3::Z * z
+}
+
+pub(crate) fn in_annulus<R: Rng + ?Sized>(rng: &mut R, center: Vec2, inner: f32, outer: f32) -> Vec2 {
+  assert!(
+    0.0 <= inner && inner <= outer,
+    "point_in_annulus: requires 0 <= inner <= outer"
+  );
+  // Area grows with r², so sample r² uniformly
+  let r = rng.gen_range(inner * inner..=outer * outer).sqrt();
+  center + unit_vec2(rng) * r
+}
+
+pub(crate) fn in_rect<R: Rng + ?Sized>(rng: &mut R, rect: Rect) -> Vec2 {
+  Vec2::new(
+    rng.gen_range(rect.min.x..=rect.max.x),
+    rng.gen_range(rect.min.y..=rect.max.y),
+  )
+}
+
+pub(crate) fn in_polygon<R: Rng + ?Sized>(rng: &mut R, polygon: &[Vec2]) -> Vec2 {
+  assert!(
+    polygon.len() >= 3 && area(polygon) > f32::EPSILON,
+    "point_in_polygon: requires at least 3 vertices and a non-zero area"
+  );
+  let bounds = polygon
+    .iter()
+    .fold(Rect::from_center_size(polygon[0], Vec2::ZERO), |r, p| {
+      r.union_point(*p)
+    });
+  // Rejection sampling: fast for any reasonably "solid" polygon,
+  // convex or not.
+  loop {
+    let point = in_rect(rng, bounds);
+    if contains(polygon, point) {
+      return point;
+    }
+  }
+}
+
+fn area(polygon: &[Vec2]) -> f32 {
+  let n = polygon.len();
+  (0..n)
+    .map(|i| polygon[i].perp_dot(polygon[(i + 1) % n]))
+    .sum::<f32>()
+    .abs() / 2.0
+}
+
+fn contains(polygon: &[Vec2], point: Vec2) -> bool {
+  // Even-odd rule: count edge crossings of a ray heading right
+  let mut inside = false;
+  let mut j = polygon.len() - 1;
+  for i in 0..polygon.len() {
+    let (a, b) = (polygon[i], polygon[j]);
+    if (a.y > point.y) != (b.y > point.y)
+      && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+    {
+      inside = !inside;
+    }
+    j = i;
+  }
+  inside
+}
+
+// Bridson's "Fast Poisson Disk Sampling in Arbitrary Dimensions": grow
+// outwards from active points, trying candidates in the annulus
+// between one and two spacings away. Stops after `limit` new points.
+pub(crate) fn poisson_disc<R: Rng + ?Sized>(
+  rng: &mut R,
+  area: Rect,
+  min_distance: f32,
+  existing: &[Vec2],
+  limit: usize,
+) -> Vec<Vec2> {
+  const ATTEMPTS: usize = 30;
+  assert!(
+    min_distance > 0.0 && area.width() >= 0.0 && area.height() >= 0.0,
+    "poisson_disc: requires a positive min_distance and a valid area"
+  );
+  // New points never share a cell, so neighbours are only 2 cells
+  // away. Existing points can be closer together, so each cell keeps
+  // a list.
+  let cell = min_distance / std::f32::consts::SQRT_2;
+  let width = (area.width() / cell).ceil() as usize + 1;
+  let height = (area.height() / cell).ceil() as usize + 1;
+  let cell_of = |p: Vec2| {
+    let c = ((p - area.min) / cell).floor();
+    c.y as usize * width + c.x as usize
+  };
+  let mut grid: Vec<Vec<usize>> = vec![Vec::new(); width * height];
+  let mut points: Vec<Vec2> = Vec::new();
+  let mut active: Vec<usize> = Vec::new();
+
+  let fits = |grid: &[Vec<usize>], points: &[Vec2], p: Vec2| {
+    let index = cell_of(p);
+    let (cx, cy) = (index % width, index / width);
+    for y in cy.saturating_sub(2)..=(cy + 2).min(height - 1) {
+      for x in cx.saturating_sub(2)..=(cx + 2).min(width - 1) {
+        let too_close = grid[y * width + x]
+          .iter()
+          .any(|i| points[*i].distance_squared(p) < min_distance * min_distance);
+        if too_close {
+          return false;
+        }
+      }
+    }
+    true
+  };
+  let add = |grid: &mut [Vec<usize>], points: &mut Vec<Vec2>, active: &mut Vec<usize>, p: Vec2| {
+    grid[cell_of(p)].push(points.len());
+    active.push(points.len());
+    points.push(p);
+  };
+
+  for p in existing.iter().filter(|p| area.contains(**p)) {
+    add(&mut grid, &mut points, &mut active, *p);
+  }
+  let first_new = points.len();
+  if limit == 0 {
+    return Vec::new();
+  }
+  if points.is_empty() {
+    add(&mut grid, &mut points, &mut active, in_rect(rng, area));
+  }
+
+  while !active.is_empty() && points.len() - first_new < limit {
+    let slot = rng.gen_range(0..active.len());
+    let origin = points[active[slot]];
+    let found = (0..ATTEMPTS)
+      .map(|_| in_annulus(rng, origin, min_distance, min_distance * 2.0))
+      .find(|p| area.contains(*p) && fits(&grid, &points, *p));
+    match found {
+      Some(p) => add(&mut grid, &mut points, &mut active, p),
+      None => {
+        active.swap_remove(slot);
+      }
+    }
+  }
+  points.split_off(first_new)
+}
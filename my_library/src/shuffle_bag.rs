@@ -0,0 +1,146 @@
+use bevy::prelude::{Component, Resource};
+use rand::{Rng, seq::SliceRandom};
+
+/// `ShuffleBag` deals items in a random order without repeating any
+/// until every item has been dealt---like drawing pieces from a bag
+/// in Tetris, then refilling it. Compared to picking at random each
+/// time, it avoids long streaks and droughts.
+///
+/// Put an item in more than once to make it proportionally more
+/// common. The bag never deals the same item twice in a row across a
+/// refill, unless it only holds one distinct item.
+///
+/// It can be used as a `Component` (one bag per entity) or a
+/// `Resource`.
+///
+/// ## Example
+///
+/// ```
+/// use my_library::{RandomNumberGenerator, ShuffleBag};
+/// let mut rng = RandomNumberGenerator::seeded(1);
+/// let mut bag = ShuffleBag::new(vec!['I', 'O', 'T', 'S', 'Z', 'J', 'L']);
+/// let mut first_seven: Vec<char> = (0..7).map(|_| *bag.deal(&mut rng).unwrap()).collect();
+/// first_seven.sort();
+/// assert_eq!(first_seven, vec!['I', 'J', 'L', 'O', 'S', 'T', 'Z']);
+/// ```
+#[derive(Component, Resource, Clone, Debug)]
+pub struct ShuffleBag<T> {
+  items: Vec<T>,
+  // Indices into `items` still to be dealt, dealt from the back
+  remaining: Vec<usize>,
+  last: Option<usize>,
+}
+
+impl<T> ShuffleBag<T> {
+  /// Creates a bag holding `items`.
+  pub fn new(items: Vec<T>) -> Self {
+    Self {
+      items,
+      remaining: Vec::new(),
+      last: None,
+    }
+  }
+
+  /// Adds `copies` of `item` to the bag. The extra copies join the
+  /// next refill.
+  pub fn with(mut self, item: T, copies: usize) -> Self
+  where T: Clone
+  {
+    for _ in 0..copies {
+      self.items.push(item.clone());
+    }
+    self
+  }
+
+  /// Deals the next item, refilling and reshuffling the bag when it
+  /// runs out. Returns `None` only if the bag holds no items at all.
+  pub fn deal<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<&T>
+  where T: PartialEq
+  {
+    if self.items.is_empty() {
+      return None;
+    }
+    if self.remaining.is_empty() {
+      self.refill(rng);
+    }
+    let index = self.remaining.pop()?;
+    self.last = Some(index);
+    Some(&self.items[index])
+  }
+
+  fn refill<R: Rng + ?Sized>(&mut self, rng: &mut R)
+  where T: PartialEq
+  {
+    self.remaining = (0..self.items.len()).collect();
+    self.remaining.shuffle(rng);
+    // Don't repeat the last item of the previous bag straight away
+    if let Some(last) = self.last {
+      let repeats = |i: usize| self.items[i] == self.items[last];
+      let top = self.remaining.len() - 1;
+      if repeats(self.remaining[top]) {
+        if let Some(swap) = self.remaining.iter().position(|i| !repeats(*i)) {
+          self.remaining.swap(swap, top);
+        }
+      }
+    }
+  }
+
+  /// How many items are left before the bag refills.
+  pub fn remaining(&self) -> usize {
+    self.remaining.len()
+  }
+
+  /// How many items the bag holds when full.
+  pub fn len(&self) -> usize {
+    self.items.len()
+  }
+
+  /// Whether the bag holds no items at all.
+  pub fn is_empty(&self) -> bool {
+    self.items.is_empty()
+  }
+
+  /// Empties the bag, so the next deal starts a fresh shuffle.
+  pub fn reset(&mut self) {
+    self.remaining.clear();
+    self.last = None;
+  }
+}
+
+impl<T> FromIterator<T> for ShuffleBag<T> {
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    Self::new(iter.into_iter().collect())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::RandomNumberGenerator;
+
+  #[test]
+  fn test_deals_every_item() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let mut bag: ShuffleBag<i32> = (-5..5).collect();
+    for _ in 0..10 {
+      let mut dealt: Vec<i32> = (0..10).map(|_| *bag.deal(&mut rng).unwrap()).collect();
+      dealt.sort();
+      assert_eq!(dealt, (-5..5).collect::<Vec<i32>>());
+    }
+  }
+
+  #[test]
+  fn test_no_repeat_across_refill() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let mut bag = ShuffleBag::new(vec![1, 2]).with(3, 2);
+    let mut last = *bag.deal(&mut rng).unwrap();
+    for _ in 0..1000 {
+      let next = *bag.deal(&mut rng).unwrap();
+      if bag.remaining() == bag.len() - 1 {
+        assert_ne!(next, last);
+      }
+      last = next;
+    }
+    assert!(ShuffleBag::<i32>::new(Vec::new()).deal(&mut rng).is_none());
+  }
+}
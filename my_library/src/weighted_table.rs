@@ -0,0 +1,283 @@
+use serde::{Deserialize, Serialize};
+use rand::Rng;
+
+/// What a [`WeightedEntry`] produces when it is picked.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TableEntry<T> {
+  /// A single value.
+  Item(T),
+  /// Another table, which is rolled in turn. This lets you build
+  /// "pick a rarity, then pick an item of that rarity" tables.
+  Table(WeightedTable<T>),
+}
+
+/// One weighted option in a [`WeightedTable`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+pub struct WeightedEntry<T> {
+  /// Relative likelihood of this entry. An entry with weight 2 is
+  /// twice as likely as an entry with weight 1.
+  pub weight: u32,
+  /// What is produced when the entry is picked.
+  pub value: TableEntry<T>,
+  /// If set, the entry is forced once it has been passed over this
+  /// many times in a row ("pity timer").
+  #[serde(default)]
+  pub pity: Option<u32>,
+  #[serde(skip)]
+  misses: u32,
+}
+
+/// `WeightedTable` picks entries by relative weight, such as a loot
+/// table or a spawn table.
+///
+/// Entries may be nested tables, and may carry a pity timer that
+/// guarantees them after a number of misses. Guaranteed entries are
+/// included in every [`roll`](WeightedTable::roll), on top of the
+/// weighted pick.
+///
+/// Tables can be built in code, or loaded from a `.table.ron` file
+/// with [`AssetManager::add_weighted_table`](crate::AssetManager::add_weighted_table).
+///
+/// ## Example
+///
+/// ```
+/// use my_library::{RandomNumberGenerator, WeightedTable};
+/// let mut rng = RandomNumberGenerator::seeded(1);
+/// let mut loot = WeightedTable::new()
+///   .with("copper", 10)
+///   .with("silver", 4)
+///   .with_pity("gold", 1, 20)
+///   .with_guaranteed("rations");
+/// let drops = loot.roll(&mut rng);
+/// assert_eq!(drops[0], "rations");
+/// assert_eq!(drops.len(), 2);
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+pub struct WeightedTable<T> {
+  /// Values included in every roll.
+  #[serde(default)]
+  pub guaranteed: Vec<T>,
+  /// The weighted options.
+  #[serde(default)]
+  pub entries: Vec<WeightedEntry<T>>,
+}
+
+impl<T> Default for WeightedTable<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> WeightedTable<T> {
+  /// Creates an empty table.
+  pub fn new() -> Self {
+    Self {
+      guaranteed: Vec::new(),
+      entries: Vec::new(),
+    }
+  }
+
+  /// Adds a value with the given weight.
+  pub fn with(self, value: T, weight: u32) -> Self {
+    self.with_entry(TableEntry::Item(value), weight, None)
+  }
+
+  /// Adds a nested table with the given weight. When it is picked,
+  /// the nested table is rolled as well.
+  pub fn with_table(self, table: WeightedTable<T>, weight: u32) -> Self {
+    self.with_entry(TableEntry::Table(table), weight, None)
+  }
+
+  /// Adds a value with the given weight, which is forced if it has
+  /// not been picked in `pity` consecutive picks.
+  pub fn with_pity(self, value: T, weight: u32, pity: u32) -> Self {
+    self.with_entry(TableEntry::Item(value), weight, Some(pity))
+  }
+
+  /// Adds a value that is included in every roll.
+  pub fn with_guaranteed(mut self, value: T) -> Self {
+    self.guaranteed.push(value);
+    self
+  }
+
+  fn with_entry(mut self, value: TableEntry<T>, weight: u32, pity: Option<u32>) -> Self {
+    self.entries.push(WeightedEntry { weight, value, pity, misses: 0 });
+    self
+  }
+
+  /// The sum of all entry weights. It's a `u64`, so even a table
+  /// full of `u32::MAX` weights can't overflow it.
+  pub fn total_weight(&self) -> u64 {
+    self.entries.iter().map(|e| u64::from(e.weight)).sum()
+  }
+
+  /// Forgets all pity timer progress, including in nested tables.
+  pub fn reset_pity(&mut self) {
+    for entry in self.entries.iter_mut() {
+      entry.misses = 0;
+      if let TableEntry::Table(table) = &mut entry.value {
+        table.reset_pity();
+      }
+    }
+  }
+
+  /// Picks a single value by weight, descending into nested tables.
+  /// Guaranteed values are not included.
+  ///
+  /// Returns `None` if the table (or the nested table picked) is
+  /// empty or has no weight.
+  pub fn pick<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<&T> {
+    let index = self.pick_index(rng)?;
+    match &mut self.entries[index].value {
+      TableEntry::Item(value) => Some(value),
+      TableEntry::Table(table) => table.pick(rng),
+    }
+  }
+
+  /// Picks a single value by weight, like [`pick`](WeightedTable::pick),
+  /// but without touching the table. Pity timers are ignored, so the
+  /// result depends only on `rng`: the same generator state always
+  /// gives the same value.
+  pub fn pick_stateless<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&T> {
+    let index = self.weighted_index(rng)?;
+    match &self.entries[index].value {
+      TableEntry::Item(value) => Some(value),
+      TableEntry::Table(table) => table.pick_stateless(rng),
+    }
+  }
+
+  /// Rolls the table: every guaranteed value, followed by the result
+  /// of a weighted pick (including the guaranteed values of a picked
+  /// nested table).
+  pub fn roll<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Vec<T>
+  where T: Clone
+  {
+    let mut result = self.guaranteed.clone();
+    self.roll_into(rng, &mut result);
+    result
+  }
+
+  fn roll_into<R: Rng + ?Sized>(&mut self, rng: &mut R, result: &mut Vec<T>)
+  where T: Clone
+  {
+    if let Some(index) = self.pick_index(rng) {
+      match &mut self.entries[index].value {
+        TableEntry::Item(value) => result.push(value.clone()),
+        TableEntry::Table(table) => {
+          result.extend(table.guaranteed.iter().cloned());
+          table.roll_into(rng, result);
+        }
+      }
+    }
+  }
+
+  fn pick_index<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<usize> {
+    let forced = self.entries.iter().position(|e| {
+      matches!(e.pity, Some(pity) if e.misses >= pity)
+    });
+    let index = match forced {
+      Some(index) => index,
+      None => self.weighted_index(rng)?,
+    };
+    for (i, entry) in self.entries.iter_mut().enumerate() {
+      if i == index {
+        entry.misses = 0;
+      } else if entry.pity.is_some() {
+        entry.misses += 1;
+      }
+    }
+    Some(index)
+  }
+
+  fn weighted_index<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<usize> {
+    let total = self.total_weight();
+    if total == 0 {
+      return None;
+    }
+    let mut roll = rng.gen_range(0..total);
+    self.entries.iter().position(|e| {
+      let weight = u64::from(e.weight);
+      if roll < weight {
+        true
+      } else {
+        roll -= weight;
+        false
+      }
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::RandomNumberGenerator;
+
+  #[test]
+  fn test_weights() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let mut table = WeightedTable::new().with(1, 3).with(2, 1).with(3, 0);
+    let mut counts = [0; 4];
+    for _ in 0..10_000 {
+      counts[*table.pick(&mut rng).unwrap()] += 1;
+    }
+    assert_eq!(counts[3], 0);
+    assert!(counts[1] > counts[2] * 2);
+  }
+
+  #[test]
+  fn test_pity() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let mut table = WeightedTable::new().with("common", 1000).with_pity("rare", 1, 5);
+    let mut since_rare = 0;
+    for _ in 0..100 {
+      if *table.pick(&mut rng).unwrap() == "rare" {
+        since_rare = 0;
+      } else {
+        since_rare += 1;
+      }
+      assert!(since_rare <= 5);
+    }
+  }
+
+  #[test]
+  fn test_nested() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let rare = WeightedTable::new().with("sword", 1).with_guaranteed("chest");
+    let mut table = WeightedTable::new().with_table(rare, 1);
+    assert_eq!(table.roll(&mut rng), vec!["chest", "sword"]);
+    assert!(WeightedTable::<i32>::new().pick(&mut rng).is_none());
+  }
+
+  #[test]
+  fn test_ron() {
+    let table: WeightedTable<i32> = ron::from_str(
+      "(entries: [(weight: 2, value: Item(0)), (weight: 1, value: Table((entries: [(weight: 1, value: Item(4), pity: Some(3))])))])"
+    ).unwrap();
+    assert_eq!(table.total_weight(), 3);
+    assert!(table.guaranteed.is_empty());
+  }
+  #[test]
+  fn test_huge_weights() {
+    let mut rng = RandomNumberGenerator::seeded(1);
+    let mut table = WeightedTable::new().with(1, u32::MAX).with(2, u32::MAX).with(3, 1);
+    assert_eq!(table.total_weight(), 2 * u64::from(u32::MAX) + 1);
+    let mut counts = [0; 4];
+    for _ in 0..1000 {
+      counts[*table.pick(&mut rng).unwrap()] += 1;
+    }
+    assert!(counts[1] > 0 && counts[2] > 0);
+  }
+  #[test]
+  fn test_pick_stateless() {
+    let rare = WeightedTable::new().with("sword", 1);
+    let table = WeightedTable::new().with("common", 1000).with_pity("rare", 1, 5).with_table(rare, 1);
+    let picks = |seed: u64| {
+      let mut rng = RandomNumberGenerator::seeded(seed);
+      (0..50).map(|_| *table.pick_stateless(&mut rng).unwrap()).collect::<Vec<_>>()
+    };
+    assert_eq!(picks(1), picks(1));
+    assert!(picks(1).iter().all(|v| ["common", "rare", "sword"].contains(v)));
+  }
+}
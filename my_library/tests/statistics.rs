@@ -0,0 +1,196 @@
+//! Statistical quality checks for `RandomNumberGenerator`, run against
+//! every algorithm. Run with `--features locking` to check the locking
+//! generator too.
+//!
+//! Every test uses fixed seeds, so results are repeatable; thresholds
+//! are set at a 0.1% significance level, so a failure means a real
+//! problem rather than bad luck. Failures print a histogram of what was
+//! drawn, like the `random_distribution` example.
+use my_library::{Algorithm, RandomNumberGenerator};
+
+const SAMPLES: usize = 100_000;
+
+fn generators() -> Vec<(String, RandomNumberGenerator)> {
+  let mut generators: Vec<_> = Algorithm::ALL
+    .into_iter()
+    .map(|algorithm| (format!("{algorithm:?}"), RandomNumberGenerator::with_algorithm(algorithm, 42)))
+    .collect();
+  generators.push(("default".to_string(), RandomNumberGenerator::seeded(42)));
+  generators
+}
+
+// The chi-square value a result must stay under, for `df` degrees of
+// freedom at the 0.1% level (Wilson-Hilferty approximation).
+fn chi_square_limit(df: usize) -> f64 {
+  const Z: f64 = 3.09;
+  let df = df as f64;
+  let k = 2.0 / (9.0 * df);
+  df * (1.0 - k + Z * k.sqrt()).powi(3)
+}
+
+fn chi_square(counts: &[usize], expected: &[f64]) -> f64 {
+  counts
+    .iter()
+    .zip(expected)
+    .map(|(count, expected)| (*count as f64 - expected).powi(2) / expected)
+    .sum()
+}
+
+fn histogram(counts: &[usize], first: i64) -> String {
+  let max = counts.iter().copied().max().unwrap_or(1).max(1);
+  counts
+    .iter()
+    .enumerate()
+    .map(|(i, count)| {
+      let bar = "#".repeat(count * 60 / max);
+      format!("{: >4} : {bar} {count}\n", i as i64 + first)
+    })
+    .collect()
+}
+
+#[test]
+fn chi_square_range() {
+  for (name, rng) in generators().iter_mut() {
+    for sides in [2, 6, 10, 37, 100] {
+      let mut counts = vec![0; sides];
+      for _ in 0..SAMPLES {
+        counts[rng.range(0..sides)] += 1;
+      }
+      let expected = vec![SAMPLES as f64 / sides as f64; sides];
+      let chi = chi_square(&counts, &expected);
+      assert!(
+        chi < chi_square_limit(sides - 1),
+        "{name}: range(0..{sides}) chi-square {chi:.1}\n{}",
+        histogram(&counts, 0)
+      );
+    }
+  }
+}
+
+#[test]
+fn chi_square_inclusive_range() {
+  for (name, rng) in generators().iter_mut() {
+    let mut counts = vec![0; 10];
+    for _ in 0..SAMPLES {
+      counts[rng.range(1..=10_i64) as usize - 1] += 1;
+    }
+    let expected = vec![SAMPLES as f64 / 10.0; 10];
+    let chi = chi_square(&counts, &expected);
+    assert!(
+      chi < chi_square_limit(9),
+      "{name}: range(1..=10) chi-square {chi:.1}\n{}",
+      histogram(&counts, 1)
+    );
+  }
+}
+
+#[test]
+fn chi_square_3d6() {
+  // Exact odds of each 3d6 total, out of 216
+  let mut ways = [0_usize; 16];
+  for a in 1..=6 {
+    for b in 1..=6 {
+      for c in 1..=6 {
+        ways[a + b + c - 3] += 1;
+      }
+    }
+  }
+  let expected: Vec<f64> = ways.iter().map(|w| *w as f64 * SAMPLES as f64 / 216.0).collect();
+  for (name, rng) in generators().iter_mut() {
+    let mut counts = vec![0; 16];
+    for _ in 0..SAMPLES {
+      counts[rng.roll_dice("3d6").unwrap().total as usize - 3] += 1;
+    }
+    let chi = chi_square(&counts, &expected);
+    assert!(
+      chi < chi_square_limit(15),
+      "{name}: 3d6 chi-square {chi:.1}\n{}",
+      histogram(&counts, 3)
+    );
+  }
+}
+
+// Kolmogorov-Smirnov: the largest gap between the samples' cumulative
+// distribution and the expected one.
+fn ks_statistic(mut samples: Vec<f64>, cdf: impl Fn(f64) -> f64) -> f64 {
+  samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+  let n = samples.len() as f64;
+  samples
+    .iter()
+    .enumerate()
+    .map(|(i, x)| {
+      let f = cdf(*x);
+      (f - i as f64 / n).max((i + 1) as f64 / n - f)
+    })
+    .fold(0.0, f64::max)
+}
+
+fn ks_limit(n: usize) -> f64 {
+  1.95 / (n as f64).sqrt()
+}
+
+#[test]
+fn kolmogorov_smirnov_floats() {
+  for (name, rng) in generators().iter_mut() {
+    let f64s: Vec<f64> = (0..SAMPLES).map(|_| rng.next::<f64>()).collect();
+    let d = ks_statistic(f64s, |x| x.clamp(0.0, 1.0));
+    assert!(d < ks_limit(SAMPLES), "{name}: next::<f64>() KS {d:.5}");
+
+    let f32s: Vec<f64> = (0..SAMPLES).map(|_| rng.range(-2.0_f32..3.0) as f64).collect();
+    let d = ks_statistic(f32s, |x| ((x + 2.0) / 5.0).clamp(0.0, 1.0));
+    assert!(d < ks_limit(SAMPLES), "{name}: range(-2.0..3.0) KS {d:.5}");
+  }
+}
+
+#[test]
+fn kolmogorov_smirnov_exponential() {
+  for (name, rng) in generators().iter_mut() {
+    let samples: Vec<f64> = (0..SAMPLES).map(|_| rng.exponential(2.0) as f64).collect();
+    let d = ks_statistic(samples, |x| 1.0 - (-2.0 * x.max(0.0)).exp());
+    assert!(d < ks_limit(SAMPLES), "{name}: exponential(2.0) KS {d:.5}");
+  }
+}
+
+fn correlation(a: &[f64], b: &[f64]) -> f64 {
+  let n = a.len() as f64;
+  let (mean_a, mean_b) = (a.iter().sum::<f64>() / n, b.iter().sum::<f64>() / n);
+  let cov: f64 = a.iter().zip(b).map(|(x, y)| (x - mean_a) * (y - mean_b)).sum();
+  let var_a: f64 = a.iter().map(|x| (x - mean_a).powi(2)).sum();
+  let var_b: f64 = b.iter().map(|y| (y - mean_b).powi(2)).sum();
+  cov / (var_a * var_b).sqrt()
+}
+
+#[test]
+fn serial_correlation() {
+  for (name, rng) in generators().iter_mut() {
+    let samples: Vec<f64> = (0..SAMPLES).map(|_| rng.next::<f64>()).collect();
+    for lag in [1, 2, 3, 7] {
+      let r = correlation(&samples[..SAMPLES - lag], &samples[lag..]);
+      // About 3.3 standard errors either side of zero
+      assert!(
+        r.abs() < 3.3 / (SAMPLES as f64).sqrt(),
+        "{name}: lag {lag} serial correlation {r:.5}"
+      );
+    }
+  }
+}
+
+#[test]
+fn bit_balance() {
+  const DRAWS: usize = 20_000;
+  for (name, rng) in generators().iter_mut() {
+    let mut ones = [0_usize; 64];
+    for _ in 0..DRAWS {
+      let n: u64 = rng.next();
+      for (bit, count) in ones.iter_mut().enumerate() {
+        *count += (n >> bit) as usize & 1;
+      }
+    }
+    // Each bit is a fair coin: count ~ Normal(n/2, n/4)
+    let sd = (DRAWS as f64 / 4.0).sqrt();
+    for (bit, count) in ones.iter().enumerate() {
+      let z = (*count as f64 - DRAWS as f64 / 2.0) / sd;
+      assert!(z.abs() < 4.0, "{name}: bit {bit} set {count} times of {DRAWS}");
+    }
+  }
+}